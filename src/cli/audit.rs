@@ -0,0 +1,27 @@
+use failure::Error;
+use structopt::StructOpt;
+
+use crate::cli::history::HistoryArgs;
+use crate::cli::normalize::NormalizeArgs;
+use crate::cli::Report;
+use crate::suggest::audit::{audit as audit_aliases, build_tables};
+
+/// Represents command line arguments for the `audit` sub-command
+#[derive(Debug, StructOpt)]
+pub struct AuditArgs {
+    #[structopt(flatten)]
+    pub history: HistoryArgs,
+    #[structopt(flatten)]
+    pub normalize: NormalizeArgs,
+}
+
+/// Outputs a table of the aliased commands still typed in full, followed by a table of the
+/// aliases never used
+pub fn audit(args: AuditArgs) -> Result<Report, Error> {
+    let rc = args.normalize.startup_files()?;
+    let mut commands = args.history.commands()?;
+    let normalizer = args.normalize.normalizer(&rc);
+    let usages = audit_aliases(commands.by_ref(), &rc, &normalizer);
+    commands.finish()?;
+    Ok(Report::Tables(build_tables(usages)))
+}
@@ -0,0 +1,213 @@
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::vec;
+
+use failure::{err_msg, format_err, Error, ResultExt};
+use structopt::StructOpt;
+
+use crate::history::{
+    CommandStream, Diagnostic, Diagnostics, Environment, ExecutedCommand, Format, locate,
+    ParseOptions,
+};
+
+/// Represents command line arguments describing the history file to analyze
+#[derive(Debug, StructOpt)]
+pub struct HistoryArgs {
+    /// Paths to history files, or `-` to read from standard input [default: the current user's
+    /// history file]
+    #[structopt(name = "histfile", parse(from_os_str))]
+    pub history_files: Vec<PathBuf>,
+    /// Format of the history files, detected from the contents of each if omitted
+    #[structopt(
+        long = "format",
+        raw(possible_values = "Format::VARIANTS", case_insensitive = "true")
+    )]
+    pub format: Option<Format>,
+    /// Analyze whole command lines instead of splitting them into individual commands
+    #[structopt(long = "whole-lines")]
+    pub whole_lines: bool,
+    /// Keep the paths fish records with each command, so that only they are replaced with
+    /// placeholders in suggested functions
+    #[structopt(long = "fish-paths")]
+    pub fish_paths: bool,
+    /// Fail on the first line of a history file that can't be parsed instead of skipping it
+    #[structopt(long = "strict")]
+    pub strict: bool,
+    /// Report how many lines of each history file were parsed, and which were skipped
+    #[structopt(short = "v", long = "verbose")]
+    pub verbose: bool,
+}
+
+impl HistoryArgs {
+    /// Produces an iterator over the commands of the history files, read one after another
+    ///
+    /// If no history files were given, the current user's history file is read instead.
+    pub fn commands(&self) -> Result<Commands<'_>, Error> {
+        let files = if self.history_files.is_empty() {
+            vec![self.default_file()?]
+        } else {
+            let files = self.history_files.iter();
+            files.map(|path| (path.to_owned(), self.format)).collect()
+        };
+        Ok(Commands {
+            args: self,
+            files: files.into_iter(),
+            current: None,
+            opened: Rc::default(),
+            error: None,
+        })
+    }
+
+    /// Removes a count given after the history files, as in `rags suggest histfile 10`
+    ///
+    /// This form is deprecated in favor of `--count`, since a count can't always be told apart
+    /// from a history file, so a warning is printed. Numbers naming existing files are still read
+    /// as history files.
+    pub fn take_positional_count(&mut self) -> Option<usize> {
+        if self.history_files.len() < 2 {
+            return None;
+        }
+        let last = self.history_files.last()?;
+        let count = last.to_str()?.parse().ok().filter(|_| !last.exists())?;
+        self.history_files.pop();
+        eprintln!(
+            "Warning: the count after the history file is deprecated, use `--count {}`",
+            count
+        );
+        Some(count)
+    }
+
+    /// Finds the current user's history file and its format
+    fn default_file(&self) -> Result<(PathBuf, Option<Format>), Error> {
+        let (path, format) = locate(&Environment::current())
+            .ok_or_else(|| err_msg("Unable to find a history file, please provide one"))?;
+        let format = self.format.or(format);
+        match format {
+            Some(format) => eprintln!("Using {} history file {}", format, path.display()),
+            None => eprintln!("Using history file {}", path.display()),
+        }
+        Ok((path, format))
+    }
+
+    /// Opens a single history file, treating `-` as standard input
+    fn open(&self, path: &Path, format: Option<Format>) -> Result<Source, Error> {
+        let options = ParseOptions {
+            format,
+            whole_lines: self.whole_lines,
+            fish_paths: self.fish_paths,
+        };
+        let (name, stream) = if path == Path::new("-") {
+            let stream = CommandStream::new(io::stdin().lock(), options);
+            ("standard input".to_string(), stream)
+        } else {
+            let name = path.display().to_string();
+            let file = File::open(path)
+                .with_context(|_| format!("Unable to open history file {}", name))?;
+            (name, CommandStream::new(BufReader::new(file), options))
+        };
+        Ok(Source {
+            name,
+            stream,
+            skipped: Vec::new(),
+        })
+    }
+
+    /// Finishes reading a history file, reporting the lines that were skipped if `verbose` is set
+    fn close(&self, source: Source) -> Result<(), Error> {
+        let Source { name, stream, skipped } = source;
+        let parsed = stream.parsed();
+        let lines = stream
+            .finish()
+            .with_context(|_| format!("Unable to read history from {}", name))?;
+        if self.verbose {
+            let diagnostics = Diagnostics { lines, parsed, skipped };
+            eprintln!("{}: {}", name, diagnostics);
+            for diagnostic in &diagnostics.skipped {
+                eprintln!("  {}", diagnostic);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A history file that is being read
+struct Source {
+    name: String,
+    stream: CommandStream<'static>,
+    skipped: Vec<Diagnostic>,
+}
+
+/// Iterator over the commands of the history files given as command line arguments
+///
+/// Files are parsed as they are read, so they needn't fit in memory. Iteration stops at the first
+/// error, or the first malformed line if `strict` is set, which is then produced by `finish`.
+pub struct Commands<'a> {
+    args: &'a HistoryArgs,
+    files: vec::IntoIter<(PathBuf, Option<Format>)>,
+    current: Option<Source>,
+    /// Number of history files opened so far
+    opened: Rc<Cell<usize>>,
+    error: Option<Error>,
+}
+
+impl<'a> Commands<'a> {
+    /// Produces the number of history files opened so far, shared so that it can be watched while
+    /// the commands are consumed to tell when they start coming from another file
+    pub fn files_opened(&self) -> Rc<Cell<usize>> {
+        Rc::clone(&self.opened)
+    }
+
+    /// Consumes the iterator, producing the error that stopped it, if any
+    pub fn finish(self) -> Result<(), Error> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Produces the next command, or the error that prevents reading it
+    fn try_next(&mut self) -> Result<Option<ExecutedCommand>, Error> {
+        loop {
+            let source = match &mut self.current {
+                Some(source) => source,
+                None => match self.files.next() {
+                    Some((path, format)) => {
+                        self.opened.set(self.opened.get() + 1);
+                        self.current.insert(self.args.open(&path, format)?)
+                    }
+                    None => return Ok(None),
+                },
+            };
+            match source.stream.next() {
+                Some(Ok(command)) => return Ok(Some(command)),
+                Some(Err(diagnostic)) if self.args.strict => {
+                    let name = &source.name;
+                    return Err(format_err!("Malformed history file {}, {}", name, diagnostic));
+                }
+                Some(Err(diagnostic)) => source.skipped.push(diagnostic),
+                None => {
+                    if let Some(source) = self.current.take() {
+                        self.args.close(source)?;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Commands<'a> {
+    type Item = ExecutedCommand;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.error.is_some() {
+            return None;
+        }
+        self.try_next().unwrap_or_else(|error| {
+            self.error = Some(error);
+            None
+        })
+    }
+}
@@ -1,9 +1,18 @@
+use prettytable::Table;
 use structopt::StructOpt;
 
+pub use audit::audit;
+pub use slow::slow;
 pub use suggest::suggest;
 
+use crate::cli::audit::AuditArgs;
+use crate::cli::slow::SlowArgs;
 use crate::cli::suggest::SuggestArgs;
 
+mod audit;
+mod history;
+mod normalize;
+mod slow;
 mod suggest;
 
 /// Rust Alias Generator for Shells
@@ -12,4 +21,18 @@ pub enum Cli {
     /// Generates a table of suggested commands to alias
     #[structopt(name = "suggest")]
     Suggest(SuggestArgs),
+    /// Generates a table of the commands that took the most time to run
+    #[structopt(name = "slow")]
+    Slow(SlowArgs),
+    /// Generates tables of aliased commands still typed in full and of aliases never used
+    #[structopt(name = "audit")]
+    Audit(AuditArgs),
+}
+
+/// Output of a sub-command
+pub enum Report {
+    /// Tables to print one after another
+    Tables(Vec<Table>),
+    /// Lines to print as they are
+    Lines(Vec<String>),
 }
@@ -0,0 +1,85 @@
+use std::env;
+use std::path::PathBuf;
+
+use failure::{Error, ResultExt};
+use structopt::StructOpt;
+
+use crate::history::Environment;
+use crate::normalize::Normalizer;
+use crate::shell::rc::{self, StartupFiles};
+
+/// Represents command line arguments controlling how commands are normalized before counting
+#[derive(Debug, StructOpt)]
+pub struct NormalizeArgs {
+    /// Count commands run under wrappers like `sudo` or `FOO=1` separately from the bare commands
+    #[structopt(long = "keep-wrappers")]
+    pub keep_wrappers: bool,
+    /// Count commands run by path, like `/usr/bin/git`, or through `command` separately
+    #[structopt(long = "keep-paths")]
+    pub keep_paths: bool,
+    /// Only count commands run by path with the bare command if they are in a `$PATH` directory
+    #[structopt(long = "check-path", conflicts_with = "keep_paths")]
+    pub check_path: bool,
+    /// Count commands whose flags only differ in order or bundling, like `ls -la` and `ls -a -l`,
+    /// together
+    #[structopt(long = "canonical-flags")]
+    pub canonical_flags: bool,
+    /// Shell startup files whose aliases are expanded in commands and whose definitions aren't
+    /// suggested again [default: the login shell's startup file]
+    #[structopt(long = "rc", parse(from_os_str))]
+    pub rc_files: Vec<PathBuf>,
+    /// Don't read any shell startup files
+    #[structopt(long = "no-rc", conflicts_with = "rc_files")]
+    pub no_rc: bool,
+}
+
+impl NormalizeArgs {
+    /// Reads the definitions of the shell startup files, along with the files they source
+    ///
+    /// If no startup files were given, those of the current user's login shell are read instead.
+    /// Files that can't be read are skipped with a warning, unless they were given with `--rc`.
+    pub fn startup_files(&self) -> Result<StartupFiles, Error> {
+        let env = Environment::current();
+        let explicit = !self.rc_files.is_empty();
+        let files = if self.no_rc {
+            Vec::new()
+        } else if self.rc_files.is_empty() {
+            rc::locate(&env)
+        } else {
+            self.rc_files.clone()
+        };
+        let mut rc = StartupFiles::default();
+        for path in files {
+            let read = rc.read(&path, env.home.as_deref());
+            if explicit {
+                read.with_context(|_| format!("Unable to read startup file {}", path.display()))?;
+            } else if let Err(error) = read {
+                rc.unreadable.push((path, error.to_string()));
+            }
+        }
+        for (path, reason) in &rc.unreadable {
+            eprintln!("Skipped unreadable startup file {}: {}", path.display(), reason);
+        }
+        Ok(rc)
+    }
+
+    /// Produces a `Normalizer` configured by the arguments, expanding the aliases of `rc`
+    pub fn normalizer(&self, rc: &StartupFiles) -> Normalizer {
+        let path_dirs = if self.check_path {
+            let path = env::var_os("PATH").unwrap_or_default();
+            Some(env::split_paths(&path).collect())
+        } else {
+            None
+        };
+        Normalizer {
+            keep_wrappers: self.keep_wrappers,
+            keep_paths: self.keep_paths,
+            path_dirs,
+            canonical_flags: self.canonical_flags,
+            aliases: rc
+                .alias_expansions()
+                .map(|(name, expansion)| (name.to_owned(), expansion))
+                .collect(),
+        }
+    }
+}
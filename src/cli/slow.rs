@@ -0,0 +1,36 @@
+use failure::Error;
+use structopt::StructOpt;
+
+use crate::cli::history::HistoryArgs;
+use crate::cli::normalize::NormalizeArgs;
+use crate::cli::Report;
+use crate::suggest::slow::{build_table, slowest};
+
+/// Represents command line arguments for the `slow` sub-command
+#[derive(Debug, StructOpt)]
+pub struct SlowArgs {
+    #[structopt(flatten)]
+    pub history: HistoryArgs,
+    #[structopt(flatten)]
+    pub normalize: NormalizeArgs,
+    /// Number of commands to list
+    #[structopt(short = "n", long = "count", default_value = "5")]
+    pub count: usize,
+}
+
+/// Outputs a table of the commands that took the most time to run
+///
+/// Command lines are always analyzed whole, since durations are recorded for whole lines and a
+/// compound line's duration can't be attributed to any one of its commands.
+pub fn slow(mut args: SlowArgs) -> Result<Report, Error> {
+    args.history.whole_lines = true;
+    if let Some(count) = args.history.take_positional_count() {
+        args.count = count;
+    }
+    let rc = args.normalize.startup_files()?;
+    let mut commands = args.history.commands()?;
+    let normalizer = args.normalize.normalizer(&rc);
+    let slowest: Vec<_> = slowest(commands.by_ref(), args.count, &normalizer).collect();
+    commands.finish()?;
+    Ok(Report::Tables(vec![build_table(slowest)]))
+}
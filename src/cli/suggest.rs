@@ -1,28 +1,174 @@
-use std::fs::File;
+use std::collections::HashSet;
+use std::env;
 use std::path::PathBuf;
 
-use failure::{Error, ResultExt};
-use prettytable::Table;
+use chrono::Duration;
+use failure::{err_msg, Error};
 use structopt::StructOpt;
 
-use crate::history::History;
-use crate::suggest::build_table;
+use crate::cli::history::HistoryArgs;
+use crate::cli::normalize::NormalizeArgs;
+use crate::cli::Report;
+use crate::shell::names::{executables, Namer};
+use crate::shell::rc::StartupFiles;
+use crate::shell::Shell;
+use crate::suggest::{build_table, Miner, Ranking};
+use crate::suggest::fragment::{self, FragmentMiner};
+use crate::suggest::pattern::{self, PatternMiner};
+use crate::suggest::rank::Rank;
+use crate::suggest::sequence::{self, SequenceMiner};
 
 /// Represents command line arguments for the `suggest` sub-command
 #[derive(Debug, StructOpt)]
 pub struct SuggestArgs {
-    /// Path to history file
-    #[structopt(name = "histfile")]
-    pub history_file: PathBuf,
+    #[structopt(flatten)]
+    pub history: HistoryArgs,
+    #[structopt(flatten)]
+    pub normalize: NormalizeArgs,
     /// Number of aliases to suggest
-    #[structopt(name = "n", default_value = "5")]
+    #[structopt(short = "n", long = "count", default_value = "5")]
     pub count: usize,
+    /// Also suggest shell functions for commands whose arguments vary, like `git commit -m <msg>`
+    #[structopt(long = "functions")]
+    pub functions: bool,
+    /// Also suggest global aliases for repeated fragments that don't start at the command, like
+    /// `--hidden --follow`
+    #[structopt(long = "fragments")]
+    pub fragments: bool,
+    /// Also suggest shell functions for commands that are repeatedly run one after another
+    #[structopt(long = "sequences")]
+    pub sequences: bool,
+    /// Most seconds between the first and last commands of a sequence, when their times are known
+    #[structopt(long = "window", default_value = "300")]
+    pub window: u32,
+    /// How to rank the suggested aliases
+    #[structopt(
+        long = "rank",
+        default_value = "savings",
+        raw(possible_values = "Rank::VARIANTS", case_insensitive = "true")
+    )]
+    pub rank: Rank,
+    /// Halve the score of commands every this many days between their last use and the latest
+    /// command, when ranking by savings
+    #[structopt(long = "half-life")]
+    pub half_life: Option<u32>,
+    /// Output definitions of the suggestions that can be sourced by a shell instead of tables
+    #[structopt(
+        long = "emit",
+        raw(possible_values = "Shell::VARIANTS", case_insensitive = "true")
+    )]
+    pub emit: Option<Shell>,
 }
 
-/// Outputs a table of suggested command aliases
-pub fn suggest(args: SuggestArgs) -> Result<Table, Error> {
-    let hist_file = File::open(args.history_file).context("Unable to open history file")?;
-    let history = History::from(hist_file);
-    let suggestions = crate::suggest::suggest(history, args.count);
-    Ok(build_table(suggestions))
-}
\ No newline at end of file
+/// Produces a `Namer` avoiding the executables on `$PATH` and the names defined in startup files
+fn namer(rc: &StartupFiles) -> Namer {
+    let mut namer = Namer::new();
+    let path = env::var_os("PATH").unwrap_or_default();
+    let dirs: Vec<PathBuf> = env::split_paths(&path).collect();
+    namer.reserve(executables(&dirs));
+    namer.reserve(rc.names().cloned());
+    namer
+}
+
+/// Outputs tables of suggested command aliases, followed by suggested shell functions, global
+/// aliases and sequences if `functions`, `fragments` and `sequences` are set
+///
+/// If `emit` is set, their definitions in that shell are output instead.
+pub fn suggest(mut args: SuggestArgs) -> Result<Report, Error> {
+    if let Some(count) = args.history.take_positional_count() {
+        args.count = count;
+    }
+    // Only scores are weighed by recency, which the other rankers don't use
+    if args.half_life.is_some() && args.rank != Rank::Savings {
+        return Err(err_msg("--half-life can only be used with --rank savings"));
+    }
+    let rc = args.normalize.startup_files()?;
+    let mut namer = namer(&rc);
+    let mut commands = args.history.commands()?;
+    let normalizer = args.normalize.normalizer(&rc);
+    let covered: HashSet<Vec<String>> =
+        rc.expansions().map(|args| normalizer.normalize_expansion(args)).collect();
+    let mut patterns = if args.functions { Some(PatternMiner::new(&normalizer)) } else { None };
+    let mut fragments = if args.fragments { Some(FragmentMiner::new(&normalizer)) } else { None };
+    let mut sequences = if args.sequences {
+        Some(SequenceMiner::new(&normalizer, Duration::seconds(i64::from(args.window))))
+    } else {
+        None
+    };
+    // Each miner normalizes its own copy of every command, so enabling miners costs a copy and a
+    // normalization of each command apiece, besides the memory of their counts
+    let files_opened = commands.files_opened();
+    let mut file = 0;
+    let mined = commands.by_ref().inspect(|command| {
+        if let Some(miner) = &mut patterns {
+            miner.add(command.clone());
+        }
+        if let Some(miner) = &mut fragments {
+            miner.add(command.clone());
+        }
+        if let Some(miner) = &mut sequences {
+            if files_opened.get() != file {
+                file = files_opened.get();
+                miner.reset();
+            }
+            miner.add(command.clone());
+        }
+    });
+    let ranker = args.rank.ranker();
+    let ranking = Ranking {
+        half_life: args.half_life.map(|days| Duration::days(i64::from(days))),
+        ranker: &*ranker,
+        namer: &mut namer,
+    };
+    let suggestions: Vec<_> =
+        crate::suggest::suggest(mined, args.count, &normalizer, &covered, ranking).collect();
+    commands.finish()?;
+    let mut patterns: Option<Vec<_>> =
+        patterns.map(|miner| miner.into_patterns(args.count).collect());
+    let mut fragments: Option<Vec<_>> =
+        fragments.map(|miner| miner.into_fragments(args.count).collect());
+    let mut sequences: Option<Vec<_>> =
+        sequences.map(|miner| miner.into_sequences(args.count).collect());
+
+    // Name the rest in order after the suggestions, so the best ones get the most mnemonic names
+    for pattern in patterns.iter_mut().flatten() {
+        pattern.name = namer.choose(pattern.candidates());
+    }
+    for fragment in fragments.iter_mut().flatten() {
+        fragment.name = namer.choose(fragment.candidates());
+    }
+    for sequence in sequences.iter_mut().flatten() {
+        sequence.name = namer.choose(sequence.candidates());
+    }
+
+    if let Some(shell) = args.emit {
+        let unnamed = |command: String| format!("# No free name for {}", command);
+        let mut lines: Vec<String> = suggestions
+            .iter()
+            .map(|suggestion| {
+                suggestion.definition(shell).unwrap_or_else(|| unnamed(suggestion.command.clone()))
+            })
+            .collect();
+        lines.extend(patterns.iter().flatten().map(|pattern| {
+            pattern.definition(shell).unwrap_or_else(|| unnamed(pattern.body(shell)))
+        }));
+        lines.extend(fragments.iter().flatten().map(|fragment| {
+            fragment.definition(shell).unwrap_or_else(|| match fragment.name {
+                Some(_) => {
+                    format!("# {} can't expand {} anywhere in a command", shell, fragment.text())
+                }
+                None => unnamed(fragment.text()),
+            })
+        }));
+        lines.extend(sequences.iter().flatten().map(|sequence| {
+            sequence.definition(shell).unwrap_or_else(|| unnamed(sequence.body()))
+        }));
+        return Ok(Report::Lines(lines));
+    }
+
+    let mut tables = vec![build_table(suggestions)];
+    tables.extend(patterns.map(pattern::build_table));
+    tables.extend(fragments.map(fragment::build_table));
+    tables.extend(sequences.map(sequence::build_table));
+    Ok(Report::Tables(tables))
+}
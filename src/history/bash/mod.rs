@@ -0,0 +1,90 @@
+use std::iter::Peekable;
+
+use regex::Regex;
+
+use super::{Diagnostic, Entry, lexer, Reason};
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Iterator over the entries of a bash history file
+///
+/// When `HISTTIMEFORMAT` is set, bash precedes each entry with a `#<time>` line. Multi-line
+/// commands of timed entries, as saved with `lithist` enabled, are reassembled by joining lines
+/// for as long as the command is incomplete, e.g. inside a quote, here-document or loop, without
+/// going past the next timestamp. Lines without a timestamp are entries of their own, since
+/// nothing would limit an unbalanced quote from joining the rest of the file. Timestamps that
+/// can't be parsed or aren't followed by a command are produced as `Diagnostic`s.
+pub struct BashEntries<I: Iterator> {
+    lines: Peekable<I>,
+    re: Regex,
+    line_number: usize,
+}
+
+impl<I, T> BashEntries<I>
+    where
+        I: Iterator<Item=T>,
+        T: AsRef<str>,
+{
+    /// Initializes a `BashEntries` iterator
+    pub fn new(lines: I) -> Self {
+        BashEntries {
+            lines: lines.peekable(),
+            re: Regex::new(Entry::BASH_TIME_PATTERN).unwrap(),
+            line_number: 0,
+        }
+    }
+
+    /// Consumes the next line if it isn't a timestamp, keeping track of its line number
+    fn next_command_line(&mut self) -> Option<T> {
+        let re = &self.re;
+        let line = self.lines.next_if(|line| !re.is_match(line.as_ref()))?;
+        self.line_number += 1;
+        Some(line)
+    }
+
+    /// Joins the lines following `cmd` to it for as long as it's incomplete
+    fn complete_entry(&mut self, mut cmd: String) -> String {
+        while lexer::is_incomplete(&cmd) {
+            match self.next_command_line() {
+                Some(line) => {
+                    cmd.push('\n');
+                    cmd.push_str(line.as_ref());
+                }
+                None => break,
+            }
+        }
+        cmd
+    }
+}
+
+impl<I, T> Iterator for BashEntries<I>
+    where
+        I: Iterator<Item=T>,
+        T: AsRef<str>,
+{
+    type Item = Result<Entry, Diagnostic>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        let line = line.as_ref();
+        self.line_number += 1;
+        let line_number = self.line_number;
+        if !self.re.is_match(line) {
+            return Some(Ok(Entry::new(line, None)));
+        }
+
+        let time = match Entry::try_parse_bash_time(line, &self.re) {
+            Some(time) => time,
+            None => return Some(Err(Diagnostic::new(line_number, Reason::InvalidTimestamp, line))),
+        };
+        match self.next_command_line() {
+            Some(first) => {
+                let cmd = self.complete_entry(first.as_ref().to_string());
+                Some(Ok(Entry::new(cmd, Some(time))))
+            }
+            None => Some(Err(Diagnostic::new(line_number, Reason::MissingCommand, line))),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use super::BashEntries;
+use crate::history::{Diagnostic, Entry, Reason};
+use crate::testing::at;
+
+#[test]
+fn timestamped_entries() {
+    let lines = vec![
+        "#1556993411",
+        "cargo fmt",
+        "#1556993420",
+        "for f in *; do",
+        "  echo $f",
+        "done",
+    ];
+    let entries: Vec<Entry> = BashEntries::new(lines.into_iter()).map(Result::unwrap).collect();
+    assert_eq!(
+        entries,
+        vec![
+            Entry::new("cargo fmt", at(1556993411)),
+            Entry::new("for f in *; do\n  echo $f\ndone", at(1556993420)),
+        ]
+    );
+}
+
+#[test]
+fn untimestamped_entries() {
+    // An unbalanced quote doesn't join the lines after it without a timestamp to stop at
+    let lines = vec!["cargo fmt", "echo don't panic", "git status", "ls \\", "#1556993420", "ls"];
+    let entries: Vec<Entry> = BashEntries::new(lines.into_iter()).map(Result::unwrap).collect();
+    assert_eq!(
+        entries,
+        vec![
+            Entry::new("cargo fmt", None),
+            Entry::new("echo don't panic", None),
+            Entry::new("git status", None),
+            Entry::new("ls \\", None),
+            Entry::new("ls", at(1556993420)),
+        ]
+    );
+}
+
+#[test]
+fn malformed_timestamps() {
+    let lines = vec![
+        "#1556993411",
+        "#99999999999999999999",
+        "cargo fmt",
+        "#1556993420",
+        "cargo test",
+        "#1556993430",
+    ];
+    let entries: Vec<_> = BashEntries::new(lines.into_iter()).collect();
+    assert_eq!(
+        entries,
+        vec![
+            Err(Diagnostic::new(1, Reason::MissingCommand, "#1556993411")),
+            Err(Diagnostic::new(2, Reason::InvalidTimestamp, "#99999999999999999999")),
+            Ok(Entry::new("cargo fmt", None)),
+            Ok(Entry::new("cargo test", at(1556993420))),
+            Err(Diagnostic::new(6, Reason::MissingCommand, "#1556993430")),
+        ]
+    );
+}
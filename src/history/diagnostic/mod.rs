@@ -0,0 +1,85 @@
+use std::fmt;
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Maximum number of characters of a malformed line kept in a `Diagnostic`
+const SNIPPET_LENGTH: usize = 60;
+
+/// Reasons a line of a history file can't be parsed
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Reason {
+    /// The line has a timestamp that isn't a valid time
+    InvalidTimestamp,
+    /// The line is a timestamp that isn't followed by a command
+    MissingCommand,
+    /// The line doesn't belong to any entry of the history file
+    OutsideEntry,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self {
+            Reason::InvalidTimestamp => "invalid timestamp",
+            Reason::MissingCommand => "timestamp without a command",
+            Reason::OutsideEntry => "line outside of any entry",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// A line of a history file that was skipped because it couldn't be parsed
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// Line number of the malformed line, starting from 1
+    pub line: usize,
+    /// Why the line couldn't be parsed
+    pub reason: Reason,
+    /// The malformed line, shortened if it is long
+    pub snippet: String,
+}
+
+impl Diagnostic {
+    /// Initializes a `Diagnostic`, keeping the start of the malformed line as its snippet
+    pub fn new(line: usize, reason: Reason, text: &str) -> Self {
+        let mut snippet: String = text.chars().take(SNIPPET_LENGTH).collect();
+        if snippet.len() < text.len() {
+            snippet.push_str("...");
+        }
+        Diagnostic {
+            line,
+            reason,
+            snippet,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}: {:?}", self.line, self.reason, self.snippet)
+    }
+}
+
+/// Summary of how the lines of a history file were parsed
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Diagnostics {
+    /// Number of lines read from the history file
+    pub lines: usize,
+    /// Number of entries that were parsed, each of which may span several lines
+    pub parsed: usize,
+    /// Lines that were skipped, in the order they were read
+    pub skipped: Vec<Diagnostic>,
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} lines read, {} entries parsed, {} skipped",
+            self.lines,
+            self.parsed,
+            self.skipped.len()
+        )
+    }
+}
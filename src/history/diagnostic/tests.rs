@@ -0,0 +1,27 @@
+use super::{Diagnostic, Diagnostics, Reason};
+
+#[test]
+fn long_lines_are_shortened() {
+    let line = format!(": 9999999999:0;echo {}", "a".repeat(100));
+    let diagnostic = Diagnostic::new(3, Reason::InvalidTimestamp, &line);
+    assert_eq!(diagnostic.snippet, format!("{}...", &line[..60]));
+
+    let short = Diagnostic::new(3, Reason::MissingCommand, "#1556993411");
+    assert_eq!(short.snippet, "#1556993411");
+}
+
+#[test]
+fn display() {
+    let diagnostic = Diagnostic::new(7, Reason::OutsideEntry, "  when: 1556993411");
+    assert_eq!(
+        diagnostic.to_string(),
+        r#"line 7: line outside of any entry: "  when: 1556993411""#
+    );
+
+    let diagnostics = Diagnostics {
+        lines: 10,
+        parsed: 3,
+        skipped: vec![diagnostic],
+    };
+    assert_eq!(diagnostics.to_string(), "10 lines read, 3 entries parsed, 1 skipped");
+}
@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+use super::{ExecutedCommand, timestamp};
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// A command line as recorded in a history file, before it is split into commands
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    /// The command line as it was entered
+    pub cmd: String,
+    /// Time of execution
+    pub time: Option<DateTime<Utc>>,
+    /// Number of seconds the command took to run
+    pub duration: Option<u32>,
+    /// Paths the shell recorded as arguments of the command, if they were kept
+    pub paths: Vec<String>,
+}
+
+impl Entry {
+    /// Regex pattern for parsing lines of history files
+    pub const PATTERN: &'static str =
+        r"(?s)^(: (?P<time>\d+(\.\d+)?):(?P<duration>\d+);)?(?P<cmd>.*)";
+
+    /// Regex pattern for parsing the timestamp lines Bash writes when `HISTTIMEFORMAT` is set
+    pub const BASH_TIME_PATTERN: &'static str = r"^#(?P<time>\d+(\.\d+)?)$";
+
+    /// Initializes an `Entry`
+    pub fn new<S: Into<String>>(cmd: S, time: Option<DateTime<Utc>>) -> Self {
+        Entry {
+            cmd: cmd.into(),
+            time,
+            duration: None,
+            paths: Vec::new(),
+        }
+    }
+
+    /// Attempts to parse an entry from a line of text
+    ///
+    /// Fails if the line doesn't match `re` or its time is out of range.
+    pub fn try_parse(line: &str, re: &Regex) -> Option<Self> {
+        let caps = re.captures(line)?;
+        let cmd = caps.name("cmd")?;
+        let time = match caps.name("time") {
+            Some(time) => Some(timestamp::parse(time.as_str())?),
+            None => None,
+        };
+        let duration = caps.name("duration").and_then(|dur| dur.as_str().parse().ok());
+        Some(Entry {
+            duration,
+            ..Entry::new(cmd.as_str(), time)
+        })
+    }
+
+    /// Attempts to parse the time from a Bash timestamp line, e.g. `#1556993411`
+    pub fn try_parse_bash_time(line: &str, re: &Regex) -> Option<DateTime<Utc>> {
+        re.captures(line)
+            .and_then(|caps| caps.name("time"))
+            .and_then(|time| timestamp::parse(time.as_str()))
+    }
+
+    /// Produces the commands run by the entry
+    ///
+    /// Compound command lines like `make && make install` are split into individual commands
+    /// sharing the entry's time, unless `whole_line` is set. Since there is no telling which of
+    /// the individual commands took up the entry's duration, they are left without one. Each
+    /// command keeps the entry's paths that are among its arguments.
+    pub fn into_commands(self, whole_line: bool) -> Vec<ExecutedCommand> {
+        let mut commands = if whole_line {
+            vec![ExecutedCommand::new(&self.cmd, self.time)]
+        } else {
+            ExecutedCommand::split(&self.cmd, self.time)
+        };
+        if let [command] = commands.as_mut_slice() {
+            command.duration = self.duration;
+        }
+        if !self.paths.is_empty() {
+            for command in &mut commands {
+                let paths = self.paths.iter().filter(|path| command.args.contains(path));
+                command.paths = paths.cloned().collect();
+            }
+        }
+        commands
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::{TimeZone, Utc};
+use regex::Regex;
+
+use crate::testing::at;
+
+use super::{Entry, ExecutedCommand};
+
+#[test]
+fn parse_dated_format() {
+    let re = Regex::new(Entry::PATTERN).unwrap();
+    assert_eq!(
+        Entry::try_parse(": 1556993411:0;cargo fmt", &re),
+        Some(Entry {
+            cmd: "cargo fmt".to_string(),
+            time: at(1556993411),
+            duration: Some(0),
+            paths: Vec::new(),
+        })
+    );
+    assert_eq!(
+        Entry::try_parse(": 1556991281:0;cargo build --release", &re),
+        Some(Entry {
+            cmd: "cargo build --release".to_string(),
+            time: at(1556991281),
+            duration: Some(0),
+            paths: Vec::new(),
+        })
+    );
+}
+
+#[test]
+fn parse_undated_format() {
+    let re = Regex::new(Entry::PATTERN).unwrap();
+    assert_eq!(
+        Entry::try_parse("cargo fmt", &re),
+        Some(Entry {
+            cmd: "cargo fmt".to_string(),
+            time: None,
+            duration: None,
+            paths: Vec::new(),
+        })
+    );
+    assert_eq!(
+        Entry::try_parse("cargo build --release", &re),
+        Some(Entry {
+            cmd: "cargo build --release".to_string(),
+            time: None,
+            duration: None,
+            paths: Vec::new(),
+        })
+    );
+}
+
+#[test]
+fn parse_out_of_range_time() {
+    let re = Regex::new(Entry::PATTERN).unwrap();
+    assert_eq!(Entry::try_parse(": 99999999999999999999:0;cargo fmt", &re), None);
+}
+
+#[test]
+fn parse_bash_time() {
+    let re = Regex::new(Entry::BASH_TIME_PATTERN).unwrap();
+    assert_eq!(Entry::try_parse_bash_time("#1556993411", &re), at(1556993411));
+    assert_eq!(
+        Entry::try_parse_bash_time("#1556993411.5", &re),
+        Some(Utc.timestamp(1556993411, 500_000_000))
+    );
+    assert_eq!(Entry::try_parse_bash_time("cargo fmt", &re), None);
+    assert_eq!(Entry::try_parse_bash_time("# a comment", &re), None);
+}
+
+#[test]
+fn commands_of_quoted_args() {
+    let entry = Entry::new(r#"git commit -m "fix bug""#, at(1556993411));
+    assert_eq!(
+        entry.into_commands(false),
+        vec![ExecutedCommand {
+            args: vec![
+                "git".to_string(),
+                "commit".to_string(),
+                "-m".to_string(),
+                "fix bug".to_string(),
+            ],
+            time: at(1556993411),
+            duration: None,
+            paths: Vec::new(),
+        }]
+    );
+}
+
+#[test]
+fn commands_of_compound_line() {
+    let entry = || Entry::new("cargo fmt && git status", None);
+    let split: Vec<Vec<String>> = entry()
+        .into_commands(false)
+        .into_iter()
+        .map(|command| command.args)
+        .collect();
+    assert_eq!(split, vec![vec!["cargo", "fmt"], vec!["git", "status"]]);
+
+    let whole = entry().into_commands(true);
+    assert_eq!(whole.len(), 1);
+    assert_eq!(whole[0].args, vec!["cargo", "fmt", "&&", "git", "status"]);
+}
+
+#[test]
+fn durations_of_commands() {
+    let re = Regex::new(Entry::PATTERN).unwrap();
+    let entry = Entry::try_parse(": 1556993411:42;cargo test", &re).unwrap();
+    assert_eq!(entry.duration, Some(42));
+    assert_eq!(entry.into_commands(false)[0].duration, Some(42));
+
+    let compound = || Entry::try_parse(": 1556993411:42;cargo fmt && cargo test", &re).unwrap();
+    let split = compound().into_commands(false);
+    assert!(split.iter().all(|command| command.duration.is_none()));
+    assert_eq!(compound().into_commands(true)[0].duration, Some(42));
+}
+
+#[test]
+fn paths_of_commands() {
+    let entry = || Entry {
+        paths: vec!["src".to_string(), "log".to_string()],
+        ..Entry::new("ls src && cat log | grep src", None)
+    };
+    let paths: Vec<Vec<String>> =
+        entry().into_commands(false).into_iter().map(|command| command.paths).collect();
+    assert_eq!(paths, vec![vec!["src"], vec!["log"], vec!["src"]]);
+    assert_eq!(entry().into_commands(true)[0].paths, vec!["src", "log"]);
+    assert!(Entry::new("ls src", None).into_commands(false)[0].paths.is_empty());
+}
@@ -1,31 +1,46 @@
-use regex::Regex;
+use chrono::{DateTime, Utc};
+
+use super::lexer;
 
 #[cfg(test)]
 #[cfg_attr(tarpaulin, skip)]
 mod tests;
 
 /// A parsed command from a line of a history file
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct ExecutedCommand {
     /// Arguments of the command
     pub args: Vec<String>,
     /// Time of execution
-    pub time: Option<u32>,
+    pub time: Option<DateTime<Utc>>,
+    /// Number of seconds the command took to run
+    pub duration: Option<u32>,
+    /// Arguments the shell recorded as existing paths, if they were kept
+    pub paths: Vec<String>,
 }
 
 impl ExecutedCommand {
-    /// Regex pattern for parsing lines of history files
-    pub const PATTERN: &'static str = r"^(: (?P<time>\d{10}):\d+;)?(?P<cmd>.*)";
+    /// Initializes an `ExecutedCommand` from a whole command line, keeping any operators as
+    /// arguments
+    pub fn new(cmd: &str, time: Option<DateTime<Utc>>) -> Self {
+        ExecutedCommand {
+            args: lexer::split(cmd),
+            time,
+            duration: None,
+            paths: Vec::new(),
+        }
+    }
 
-    /// Attempts to parse a command from a line of text
-    pub fn try_parse(line: &str, re: &Regex) -> Option<Self> {
-        re.captures(line).and_then(|caps| {
-            caps.name("cmd").and_then(|cmd| {
-                let split = cmd.as_str().split_whitespace();
-                let args = split.map(str::to_string).collect();
-                let time = caps.name("time").map(|time| time.as_str().parse().unwrap());
-                Some(ExecutedCommand { args, time })
+    /// Splits a command line into the individual commands it runs, all executed at `time`
+    pub fn split(cmd: &str, time: Option<DateTime<Utc>>) -> Vec<Self> {
+        lexer::split_commands(cmd)
+            .into_iter()
+            .map(|args| ExecutedCommand {
+                args,
+                time,
+                duration: None,
+                paths: Vec::new(),
             })
-        })
+            .collect()
     }
 }
@@ -1,49 +1,44 @@
-use regex::Regex;
+use chrono::{DateTime, Utc};
+
+use crate::testing::at;
 
 use super::ExecutedCommand;
 
+fn command(args: &[&str], time: Option<DateTime<Utc>>) -> ExecutedCommand {
+    ExecutedCommand {
+        args: args.iter().map(|arg| arg.to_string()).collect(),
+        time,
+        duration: None,
+        paths: Vec::new(),
+    }
+}
+
 #[test]
-fn parse_dated_format() {
-    let re = Regex::new(ExecutedCommand::PATTERN).unwrap();
-    assert_eq!(
-        ExecutedCommand::try_parse(": 1556993411:0;cargo fmt", &re),
-        Some(ExecutedCommand {
-            args: vec!["cargo".to_string(), "fmt".to_string()],
-            time: Some(1556993411),
-        })
-    );
+fn whole_line() {
     assert_eq!(
-        ExecutedCommand::try_parse(": 1556991281:0;cargo build --release", &re),
-        Some(ExecutedCommand {
-            args: vec![
-                "cargo".to_string(),
-                "build".to_string(),
-                "--release".to_string(),
-            ],
-            time: Some(1556991281),
-        })
+        ExecutedCommand::new("cargo fmt && cargo test | tee log", at(1556993411)),
+        command(&["cargo", "fmt", "&&", "cargo", "test", "|", "tee", "log"], at(1556993411))
     );
 }
 
 #[test]
-fn parse_undated_format() {
-    let re = Regex::new(ExecutedCommand::PATTERN).unwrap();
+fn split_compound() {
     assert_eq!(
-        ExecutedCommand::try_parse("cargo fmt", &re),
-        Some(ExecutedCommand {
-            args: vec!["cargo".to_string(), "fmt".to_string()],
-            time: None,
-        })
+        ExecutedCommand::split("cargo fmt && cargo test | tee log; git status", at(1556993411)),
+        vec![
+            command(&["cargo", "fmt"], at(1556993411)),
+            command(&["cargo", "test"], at(1556993411)),
+            command(&["tee", "log"], at(1556993411)),
+            command(&["git", "status"], at(1556993411)),
+        ]
     );
+}
+
+#[test]
+fn split_simple() {
     assert_eq!(
-        ExecutedCommand::try_parse("cargo build --release", &re),
-        Some(ExecutedCommand {
-            args: vec![
-                "cargo".to_string(),
-                "build".to_string(),
-                "--release".to_string(),
-            ],
-            time: None,
-        })
+        ExecutedCommand::split("cargo build --release", None),
+        vec![command(&["cargo", "build", "--release"], None)]
     );
-}
\ No newline at end of file
+    assert_eq!(ExecutedCommand::split("", None), vec![]);
+}
@@ -0,0 +1,131 @@
+use std::iter::Peekable;
+
+use chrono::{DateTime, Utc};
+
+use super::{Diagnostic, Entry, Reason, timestamp};
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+const CMD_PREFIX: &str = "- cmd: ";
+const WHEN_PREFIX: &str = "when: ";
+const PATHS_KEY: &str = "paths:";
+const PATH_PREFIX: &str = "- ";
+
+/// Iterator over the entries of a fish history file
+///
+/// Fish stores history as YAML-like blocks:
+///
+/// ```text
+/// - cmd: vim src/main.rs
+///   when: 1556993411
+///   paths:
+///     - src/main.rs
+/// ```
+///
+/// The paths fish records as arguments of a command are skipped unless they are kept. Lines
+/// that aren't blank and don't belong to any entry are produced as `Diagnostic`s.
+pub struct FishEntries<I: Iterator> {
+    lines: Peekable<I>,
+    keep_paths: bool,
+    line_number: usize,
+}
+
+impl<I, T> FishEntries<I>
+    where
+        I: Iterator<Item=T>,
+        T: AsRef<str>,
+{
+    /// Initializes a `FishEntries` iterator, optionally keeping the paths of each entry
+    pub fn new(lines: I, keep_paths: bool) -> Self {
+        FishEntries {
+            lines: lines.peekable(),
+            keep_paths,
+            line_number: 0,
+        }
+    }
+
+    /// Consumes the indented fields of the current entry, returning its time and paths
+    fn parse_fields(&mut self) -> (Option<DateTime<Utc>>, Vec<String>) {
+        let mut time = None;
+        let mut paths = Vec::new();
+        let mut in_paths = false;
+        while let Some(line) = self.lines.peek() {
+            let line = line.as_ref();
+            if !line.starts_with(' ') {
+                break;
+            }
+            let field = line.trim_start();
+            if let Some(when) = field.strip_prefix(WHEN_PREFIX) {
+                time = timestamp::parse(when.trim());
+                in_paths = false;
+            } else if field == PATHS_KEY {
+                in_paths = true;
+            } else if let (true, Some(path)) = (in_paths, field.strip_prefix(PATH_PREFIX)) {
+                if self.keep_paths {
+                    paths.push(unescape(path));
+                }
+            } else {
+                in_paths = false;
+            }
+            self.lines.next();
+            self.line_number += 1;
+        }
+        (time, paths)
+    }
+}
+
+impl<I, T> Iterator for FishEntries<I>
+    where
+        I: Iterator<Item=T>,
+        T: AsRef<str>,
+{
+    type Item = Result<Entry, Diagnostic>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = self.lines.next()?;
+            let line = line.as_ref();
+            self.line_number += 1;
+            if let Some(cmd) = line.strip_prefix(CMD_PREFIX) {
+                let cmd = unescape(cmd);
+                let (time, paths) = self.parse_fields();
+                return Some(Ok(Entry {
+                    paths,
+                    ..Entry::new(cmd, time)
+                }));
+            } else if !line.trim().is_empty() {
+                let diagnostic = Diagnostic::new(self.line_number, Reason::OutsideEntry, line);
+                return Some(Err(diagnostic));
+            }
+        }
+    }
+}
+
+/// Determines whether a line begins a new entry of a fish history file
+pub fn is_entry_start(line: &str) -> bool {
+    line.starts_with(CMD_PREFIX)
+}
+
+/// Reverses fish's escaping of backslashes (`\\`) and newlines (`\n`) in history entries
+pub fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('\\') => unescaped.push('\\'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
@@ -0,0 +1,69 @@
+use super::{FishEntries, unescape};
+use crate::history::{Diagnostic, Entry, Reason};
+use crate::testing::at;
+
+const SAMPLE: &[&str] = &[
+    "- cmd: cargo fmt",
+    "  when: 1556993411",
+    "- cmd: vim src/main.rs src/cli/mod.rs",
+    "  when: 1556993420",
+    "  paths:",
+    "    - src/main.rs",
+    "    - src/cli/mod.rs",
+    "- cmd: echo done",
+];
+
+#[test]
+fn unescaping() {
+    assert_eq!(unescape("cargo fmt"), "cargo fmt");
+    assert_eq!(unescape(r"echo a\nb"), "echo a\nb");
+    assert_eq!(unescape(r"echo C:\\dir"), r"echo C:\dir");
+    assert_eq!(unescape(r"echo \t\"), r"echo \t\");
+}
+
+#[test]
+fn entries() {
+    let entries: Vec<Entry> = FishEntries::new(SAMPLE.iter(), false)
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            Entry::new("cargo fmt", at(1556993411)),
+            Entry::new("vim src/main.rs src/cli/mod.rs", at(1556993420)),
+            Entry::new("echo done", None),
+        ]
+    );
+}
+
+#[test]
+fn entries_with_paths() {
+    let paths: Vec<Vec<String>> = FishEntries::new(SAMPLE.iter(), true)
+        .map(|entry| entry.unwrap().paths)
+        .collect();
+    assert_eq!(
+        paths,
+        vec![vec![], vec!["src/main.rs".to_string(), "src/cli/mod.rs".to_string()], vec![]]
+    );
+}
+
+#[test]
+fn escaped_multiline_command() {
+    let lines = vec![r"- cmd: for f in *\n  echo $f\nend", "  when: 1556993411"];
+    let entry = FishEntries::new(lines.into_iter(), false).next().unwrap().unwrap();
+    assert_eq!(entry.cmd, "for f in *\n  echo $f\nend");
+}
+
+#[test]
+fn lines_outside_entries() {
+    let lines = vec!["", "  when: 1556993411", "- cmd: cargo fmt", "cargo test"];
+    let entries: Vec<_> = FishEntries::new(lines.into_iter(), false).collect();
+    assert_eq!(
+        entries,
+        vec![
+            Err(Diagnostic::new(2, Reason::OutsideEntry, "  when: 1556993411")),
+            Ok(Entry::new("cargo fmt", None)),
+            Err(Diagnostic::new(4, Reason::OutsideEntry, "cargo test")),
+        ]
+    );
+}
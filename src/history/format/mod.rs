@@ -0,0 +1,102 @@
+use std::{fmt, str::FromStr};
+
+use regex::Regex;
+
+use super::{fish, zsh};
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Dialects of shell history files
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// Zsh history, optionally in the extended `: <time>:<duration>;<command>` form
+    Zsh,
+    /// Bash history, optionally with `#<time>` lines written when `HISTTIMEFORMAT` is set
+    Bash,
+    /// Fish history, made up of `- cmd: <command>` blocks
+    Fish,
+    /// One command per line with no additional metadata
+    Plain,
+}
+
+impl Format {
+    /// Names of the formats, as accepted by `from_str`
+    pub const VARIANTS: &'static [&'static str] = &["zsh", "bash", "fish", "plain"];
+
+    /// Number of lines inspected when detecting the format of a history file
+    pub const SNIFF_LINES: usize = 50;
+
+    const ZSH_PATTERN: &'static str = r"^: \d+(\.\d+)?:\d+;";
+    const BASH_PATTERN: &'static str = r"^#\d+(\.\d+)?$";
+
+    /// Guesses the format of a history file from its first lines
+    ///
+    /// Each line is checked for the markers of the zsh, bash and fish dialects and the format
+    /// with the most matches wins. Files without any markers are treated as `Plain`.
+    pub fn detect<I, T>(lines: I) -> Self
+        where
+            I: IntoIterator<Item=T>,
+            T: AsRef<str>,
+    {
+        let zsh_re = Regex::new(Self::ZSH_PATTERN).unwrap();
+        let bash_re = Regex::new(Self::BASH_PATTERN).unwrap();
+
+        let (mut zsh, mut bash, mut fish) = (0, 0, 0);
+        for line in lines.into_iter().take(Self::SNIFF_LINES) {
+            let line = line.as_ref();
+            if zsh_re.is_match(line) {
+                zsh += 1;
+            } else if bash_re.is_match(line) {
+                bash += 1;
+            } else if fish::is_entry_start(line) {
+                fish += 1;
+            }
+        }
+
+        if zsh == 0 && bash == 0 && fish == 0 {
+            Format::Plain
+        } else if fish >= zsh && fish >= bash {
+            Format::Fish
+        } else if zsh >= bash {
+            Format::Zsh
+        } else {
+            Format::Bash
+        }
+    }
+
+    /// Decodes a line of a history file in this format, replacing any invalid UTF-8
+    pub fn decode(self, line: &[u8]) -> String {
+        match self {
+            Format::Zsh => String::from_utf8_lossy(&zsh::unmetafy(line)).into_owned(),
+            _ => String::from_utf8_lossy(line).into_owned(),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "zsh" => Ok(Format::Zsh),
+            "bash" => Ok(Format::Bash),
+            "fish" => Ok(Format::Fish),
+            "plain" => Ok(Format::Plain),
+            _ => Err(format!("Unknown history format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Format::Zsh => "zsh",
+            Format::Bash => "bash",
+            Format::Fish => "fish",
+            Format::Plain => "plain",
+        };
+        write!(f, "{}", name)
+    }
+}
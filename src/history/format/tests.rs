@@ -0,0 +1,42 @@
+use super::Format;
+
+#[test]
+fn detect_zsh() {
+    let lines = vec![": 1556993411:0;cargo fmt", ": 1556991281:3;cargo build"];
+    assert_eq!(Format::detect(lines), Format::Zsh);
+}
+
+#[test]
+fn detect_bash() {
+    let lines = vec!["#1556993411", "cargo fmt", "#1556991281", "cargo build"];
+    assert_eq!(Format::detect(lines), Format::Bash);
+}
+
+#[test]
+fn detect_fish() {
+    let lines = vec!["- cmd: cargo fmt", "  when: 1556993411", "- cmd: cargo build"];
+    assert_eq!(Format::detect(lines), Format::Fish);
+}
+
+#[test]
+fn detect_plain() {
+    assert_eq!(Format::detect(vec!["cargo fmt", "cargo build"]), Format::Plain);
+    assert_eq!(Format::detect(Vec::<String>::new()), Format::Plain);
+}
+
+#[test]
+fn detect_mixed() {
+    // Zsh only writes extended entries once `EXTENDED_HISTORY` is set
+    let lines = vec!["cargo fmt", ": 1556993411:0;cargo fmt", ": 1556993411:0;cargo test"];
+    assert_eq!(Format::detect(lines), Format::Zsh);
+}
+
+#[test]
+fn from_str() {
+    for name in Format::VARIANTS {
+        let format: Format = name.parse().unwrap();
+        assert_eq!(&format.to_string(), name);
+    }
+    assert_eq!("ZSH".parse(), Ok(Format::Zsh));
+    assert!("csh".parse::<Format>().is_err());
+}
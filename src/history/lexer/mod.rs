@@ -0,0 +1,592 @@
+use std::{borrow::Cow, iter::Peekable, str::Chars};
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Words that may begin a command without being the command itself, e.g. `if` in `if true`
+const RESERVED_WORDS: &[&str] = &[
+    "!", "{", "}", "if", "then", "else", "elif", "fi", "while", "until", "do", "done", "for",
+    "in", "case", "esac", "select", "function", "[[",
+];
+
+/// Characters that expand in unquoted words, such as `$HOME`, `*.rs`, `~` or `{a,b}`
+const EXPANSION_CHARS: &str = "$`*?[]{}~!";
+
+/// Reserved words opening compound commands, which are incomplete until closed
+const OPENING_WORDS: &[&str] = &["if", "case", "do", "{"];
+/// Reserved words closing compound commands
+const CLOSING_WORDS: &[&str] = &["fi", "esac", "done", "}"];
+
+/// Parts of a command line that are skipped because they don't run a command
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Skipping {
+    Nothing,
+    /// The head of a loop or function definition, like `for f in *`, up to the next control
+    /// operator or `{`
+    Head,
+    /// The head of a `case` statement, up to `in`
+    CaseHead,
+    /// A pattern of a `case` statement, up to `)`
+    CasePattern,
+    /// A test like `[[ -f x ]]`, up to `]]`
+    Test,
+}
+
+/// A token of a shell command
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    /// A word with its quotes and escapes removed
+    Word(String),
+    /// A redirection operator such as `>`, `2>&1` or `<<`
+    Redirect(String),
+    /// An operator separating commands such as `&&`, `|` or `;`
+    Control(String),
+}
+
+impl Token {
+    /// Produces the text of a token
+    pub fn into_string(self) -> String {
+        match self {
+            Token::Word(s) | Token::Redirect(s) | Token::Control(s) => s,
+        }
+    }
+}
+
+/// Splits a command into words the way a POSIX shell would, honoring quotes and escapes
+///
+/// Operators are kept as words of their own so the command line as a whole can be analyzed.
+/// Expansions such as `$HOME` or `*.rs` are left as written since the history file does not
+/// record what they expanded to. So that they can be told apart from quoted text like `'$HOME'`,
+/// words containing expansion characters are kept as written, quotes included.
+pub fn split(cmd: &str) -> Vec<String> {
+    Lexer::new(cmd).map(Token::into_string).collect()
+}
+
+/// Produces the value of a word that was kept as written, e.g. `$HOME` for `'$HOME'`
+///
+/// Expansions are still left as written, and other words are returned unchanged.
+pub fn unquote(word: &str) -> Cow<'_, str> {
+    if !has_expansion_chars(word) {
+        return Cow::Borrowed(word);
+    }
+    let mut lexer = Lexer::new(word);
+    lexer.keep_spellings = false;
+    Cow::Owned(lexer.next().map(Token::into_string).unwrap_or_default())
+}
+
+/// Determines whether a word contains characters that expand unless quoted
+fn has_expansion_chars(word: &str) -> bool {
+    word.contains(|c| EXPANSION_CHARS.contains(c))
+}
+
+/// Splits a command line into the words of each individual command it runs
+///
+/// Commands are separated by control operators (`&&`, `||`, `;`, `|`, `&`, newlines) and
+/// subshell parentheses. Commands run by substitutions such as `$(...)` precede the command
+/// containing them, as that is the order in which the shell executes them. Redirections before
+/// a command's first word, like those of compound commands in `{ make; } > log`, are dropped
+/// along with their targets.
+pub fn split_commands(cmd: &str) -> Vec<Vec<String>> {
+    let mut lexer = Lexer::new(cmd);
+    let mut commands = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut skipping = Skipping::Nothing;
+    let mut dropping_target = false;
+    for token in lexer.by_ref() {
+        if skipping != Skipping::Nothing {
+            skipping = match (skipping, &token) {
+                (Skipping::Head, Token::Control(_)) => Skipping::Nothing,
+                (Skipping::Head, Token::Word(word)) if word == "{" => Skipping::Nothing,
+                (Skipping::CaseHead, Token::Word(word)) if word == "in" => Skipping::CasePattern,
+                (Skipping::CasePattern, Token::Control(op)) if op == ")" => Skipping::Nothing,
+                (Skipping::CasePattern, Token::Word(word)) if word == "esac" => Skipping::Nothing,
+                (Skipping::Test, Token::Word(word)) if word == "]]" => Skipping::Nothing,
+                (skipping, _) => skipping,
+            };
+            continue;
+        }
+        match token {
+            Token::Control(op) => {
+                dropping_target = false;
+                if !current.is_empty() {
+                    commands.push(current.split_off(0));
+                }
+                if op == ";;" {
+                    skipping = Skipping::CasePattern;
+                }
+            }
+            Token::Word(_) if dropping_target => dropping_target = false,
+            Token::Word(word) => {
+                if !current.is_empty() || !RESERVED_WORDS.contains(&word.as_str()) {
+                    current.push(word);
+                } else {
+                    skipping = match word.as_str() {
+                        "for" | "select" | "function" => Skipping::Head,
+                        "case" => Skipping::CaseHead,
+                        "[[" => Skipping::Test,
+                        _ => Skipping::Nothing,
+                    };
+                }
+            }
+            Token::Redirect(redirect) if current.is_empty() => {
+                dropping_target = redirect.ends_with(|c| "<>|".contains(c));
+            }
+            Token::Redirect(redirect) => current.push(redirect),
+        }
+    }
+    if !current.is_empty() {
+        commands.push(current);
+    }
+
+    let mut substituted: Vec<Vec<String>> = lexer
+        .substitutions
+        .iter()
+        .flat_map(|inner| split_commands(inner))
+        .collect();
+    substituted.append(&mut commands);
+    substituted
+}
+
+/// Determines whether a command line needs more lines to be complete
+///
+/// This is the case when it ends inside a quote, substitution, here-document or compound command
+/// like `if` or `for`, with a line continuation, or with an operator such as `&&` that expects
+/// another command.
+pub fn is_incomplete(cmd: &str) -> bool {
+    let mut lexer = Lexer::new(cmd);
+    let mut last = None;
+    let mut depth = 0usize;
+    let mut at_start = true;
+    for token in lexer.by_ref() {
+        match &token {
+            Token::Control(_) => at_start = true,
+            Token::Word(word) if at_start => {
+                if OPENING_WORDS.contains(&word.as_str()) {
+                    depth += 1;
+                } else if CLOSING_WORDS.contains(&word.as_str()) {
+                    depth = depth.saturating_sub(1);
+                }
+                at_start = RESERVED_WORDS.contains(&word.as_str());
+            }
+            _ => at_start = false,
+        }
+        last = Some(token);
+    }
+    let continues = match last {
+        Some(Token::Control(op)) => ["|", "|&", "&&", "||"].contains(&op.as_str()),
+        Some(Token::Redirect(_)) => true,
+        _ => false,
+    };
+    lexer.incomplete || continues || !lexer.heredocs.is_empty() || depth > 0
+}
+
+/// Quotes a word so that a shell would read it back as a single, identical argument
+///
+/// Words without whitespace, quotes or shell metacharacters are returned unchanged, as are
+/// words containing expansion characters, which the lexer keeps as written. This keeps
+/// expansions like `$HOME` working in suggested aliases and quoted text like `'$HOME'` quoted.
+/// Redirections are also left as-is.
+pub fn quote(word: &str) -> Cow<'_, str> {
+    let is_special = |c: char| c.is_whitespace() || "'\"\\`|&;<>()#".contains(c);
+    let is_plain = !word.is_empty() && (!word.contains(is_special) || is_redirect(word));
+    if is_plain || has_expansion_chars(word) {
+        Cow::Borrowed(word)
+    } else {
+        Cow::Owned(format!("'{}'", word.replace('\'', r"'\''")))
+    }
+}
+
+/// Determines whether a word is a redirection operator as produced by the lexer
+fn is_redirect(word: &str) -> bool {
+    let mut lexer = Lexer::new(word);
+    match (lexer.next(), lexer.next()) {
+        (Some(Token::Redirect(redirect)), None) => redirect == word,
+        _ => false,
+    }
+}
+
+/// Iterator over the tokens of a command
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    /// Commands run by substitutions (`$(...)`, `` `...` ``, `<(...)`) encountered so far
+    substitutions: Vec<String>,
+    /// Delimiters of here-documents whose bodies begin after the next newline, and whether
+    /// leading tabs are stripped from their lines (`<<-`)
+    heredocs: Vec<(String, bool)>,
+    /// Whether the next word is the delimiter of a here-document, and whether it strips tabs
+    expecting_delimiter: Option<bool>,
+    /// Whether the input ended inside a quote, substitution or escape
+    incomplete: bool,
+    /// Whether words containing expansion characters are produced as written
+    keep_spellings: bool,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(cmd: &'a str) -> Self {
+        Lexer {
+            chars: cmd.chars().peekable(),
+            substitutions: Vec::new(),
+            heredocs: Vec::new(),
+            expecting_delimiter: None,
+            incomplete: false,
+            keep_spellings: true,
+        }
+    }
+
+    /// Skips the bodies of pending here-documents, which follow the newline just consumed
+    fn skip_heredocs(&mut self) {
+        for (delimiter, strip_tabs) in std::mem::take(&mut self.heredocs) {
+            loop {
+                if self.chars.peek().is_none() {
+                    // Keep the here-document pending since its body is unterminated
+                    self.heredocs.push((delimiter, strip_tabs));
+                    return;
+                }
+                let mut line = String::new();
+                while let Some(c) = self.chars.next_if(|&c| c != '\n') {
+                    line.push(c);
+                }
+                self.chars.next();
+                let line = if strip_tabs { line.trim_start_matches('\t') } else { &line };
+                if line == delimiter {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Determines whether a character ends an unquoted word
+    fn is_metachar(c: char) -> bool {
+        c.is_whitespace() || "|&;()<>".contains(c)
+    }
+
+    /// Skips blanks, line continuations and comments preceding the next token
+    fn skip_blanks(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            match c {
+                '#' => {
+                    while self.chars.next_if(|&c| c != '\n').is_some() {}
+                }
+                '\\' => {
+                    let mut lookahead = self.chars.clone();
+                    lookahead.next();
+                    if lookahead.peek() != Some(&'\n') {
+                        return;
+                    }
+                    self.chars.next();
+                    self.chars.next();
+                    self.incomplete |= self.chars.peek().is_none();
+                }
+                '\n' => return,
+                c if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Reads a control operator starting with `c`, which has already been consumed
+    fn control(&mut self, c: char) -> Token {
+        let mut op = c.to_string();
+        match c {
+            '\n' => op = ";".to_string(),
+            '|' | '&' | ';' => {
+                let doubled = self.chars.next_if_eq(&c);
+                let piped = if c == '|' && doubled.is_none() {
+                    self.chars.next_if_eq(&'&')
+                } else {
+                    None
+                };
+                op.extend(doubled.or(piped));
+            }
+            _ => {}
+        }
+        Token::Control(op)
+    }
+
+    /// Reads a redirection operator, such as `2>&1` or `<<`, preceded by `prefix`
+    fn redirect(&mut self, mut prefix: String) -> Token {
+        let c = self.chars.next().unwrap();
+        prefix.push(c);
+        if c == '<' {
+            if self.chars.next_if_eq(&'<').is_some() {
+                prefix.push('<');
+                prefix.extend(self.chars.next_if(|&c| c == '<' || c == '-'));
+            } else {
+                prefix.extend(self.chars.next_if(|&c| c == '&' || c == '>'));
+            }
+        } else {
+            prefix.extend(self.chars.next_if(|&c| c == '>' || c == '&' || c == '|'));
+        }
+        if prefix.ends_with('&') {
+            // Duplicating a file descriptor, e.g. `2>&1` or `>&-`
+            while let Some(c) = self.chars.next_if(|&c| c.is_ascii_digit() || c == '-') {
+                prefix.push(c);
+            }
+        }
+        Token::Redirect(prefix)
+    }
+
+    /// Reads text up to the `close` character matching an already consumed opening character
+    fn balanced(&mut self, open: char, close: char) -> String {
+        let mut depth = 1;
+        let mut text = String::new();
+        while let Some(c) = self.chars.next() {
+            match c {
+                '\\' => {
+                    text.push(c);
+                    text.extend(self.chars.next());
+                    continue;
+                }
+                '\'' | '"' => {
+                    text.push(c);
+                    while let Some(q) = self.chars.next() {
+                        text.push(q);
+                        if q == '\\' && c == '"' {
+                            text.extend(self.chars.next());
+                        } else if q == c {
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                c if c == close => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return text;
+                    }
+                }
+                c if c == open => depth += 1,
+                _ => {}
+            }
+            text.push(c);
+        }
+        self.incomplete = true;
+        text
+    }
+
+    /// Reads a substitution whose command is delimited by `open` and `close`
+    fn substitution(&mut self, word: &mut String, prefix: &str, open: char, close: char) {
+        let inner = self.balanced(open, close);
+        word.push_str(prefix);
+        word.push_str(&inner);
+        word.push(close);
+        // `$((...))` is arithmetic rather than a command
+        if !(open == '(' && inner.starts_with('(')) {
+            self.substitutions.push(inner);
+        }
+    }
+
+    /// Reads an expansion beginning with `$`, which has already been consumed
+    fn dollar(&mut self, word: &mut String, quoted: bool) {
+        match self.chars.peek() {
+            Some('(') => {
+                self.chars.next();
+                self.substitution(word, "$(", '(', ')');
+            }
+            Some('{') => {
+                self.chars.next();
+                let inner = self.balanced('{', '}');
+                word.push_str("${");
+                word.push_str(&inner);
+                word.push('}');
+            }
+            Some('\'') if !quoted => {
+                self.chars.next();
+                self.ansi_c_quoted(word);
+            }
+            Some('"') if !quoted => {
+                self.chars.next();
+                self.double_quoted(word);
+            }
+            _ => word.push('$'),
+        }
+    }
+
+    /// Reads the body of a single-quoted string, after the opening quote
+    fn single_quoted(&mut self, word: &mut String) {
+        for c in self.chars.by_ref() {
+            if c == '\'' {
+                return;
+            }
+            word.push(c);
+        }
+        self.incomplete = true;
+    }
+
+    /// Reads the body of a double-quoted string, after the opening quote
+    fn double_quoted(&mut self, word: &mut String) {
+        while let Some(c) = self.chars.next() {
+            match c {
+                '"' => return,
+                '\\' => match self.chars.next() {
+                    Some('\n') => {}
+                    Some(c @ '$') | Some(c @ '`') | Some(c @ '"') | Some(c @ '\\') => word.push(c),
+                    Some(c) => {
+                        word.push('\\');
+                        word.push(c);
+                    }
+                    None => word.push('\\'),
+                },
+                '$' => self.dollar(word, true),
+                '`' => self.substitution(word, "`", '`', '`'),
+                c => word.push(c),
+            }
+        }
+        self.incomplete = true;
+    }
+
+    /// Reads the body of an ANSI-C quoted string (`$'...'`), after the opening quote
+    fn ansi_c_quoted(&mut self, word: &mut String) {
+        while let Some(c) = self.chars.next() {
+            match c {
+                '\'' => return,
+                '\\' => match self.chars.next() {
+                    Some('n') => word.push('\n'),
+                    Some('t') => word.push('\t'),
+                    Some('r') => word.push('\r'),
+                    Some('a') => word.push('\x07'),
+                    Some('b') => word.push('\x08'),
+                    Some('e') | Some('E') => word.push('\x1b'),
+                    Some('f') => word.push('\x0c'),
+                    Some('v') => word.push('\x0b'),
+                    Some('x') => {
+                        let digits = self.take_digits(16, 2);
+                        match u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32) {
+                            Some(c) => word.push(c),
+                            None => word.push_str(&format!("\\x{}", digits)),
+                        }
+                    }
+                    Some(d @ '0'..='7') => {
+                        let digits = d.to_string() + &self.take_digits(8, 2);
+                        let code = u32::from_str_radix(&digits, 8).unwrap();
+                        word.extend(char::from_u32(code));
+                    }
+                    Some(c) => match c {
+                        '\\' | '\'' | '"' | '?' => word.push(c),
+                        c => {
+                            word.push('\\');
+                            word.push(c);
+                        }
+                    },
+                    None => word.push('\\'),
+                },
+                c => word.push(c),
+            }
+        }
+        self.incomplete = true;
+    }
+
+    /// Consumes up to `max` digits in the given radix
+    fn take_digits(&mut self, radix: u32, max: usize) -> String {
+        let mut digits = String::new();
+        while digits.len() < max {
+            match self.chars.next_if(|c| c.is_digit(radix)) {
+                Some(c) => digits.push(c),
+                None => break,
+            }
+        }
+        digits
+    }
+
+    /// Reads a word, stopping at the first unquoted metacharacter
+    fn word(&mut self) -> Token {
+        let start = self.chars.clone();
+        let mut word = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if Self::is_metachar(c) {
+                let mut lookahead = self.chars.clone();
+                lookahead.next();
+                let is_redirect = c == '<' || c == '>';
+                if is_redirect && lookahead.peek() == Some(&'(') {
+                    // Process substitution, e.g. `<(sort a)`
+                    self.chars.next();
+                    self.chars.next();
+                    self.substitution(&mut word, &format!("{}(", c), '(', ')');
+                    continue;
+                }
+                if is_redirect && !word.is_empty() && word.chars().all(|c| c.is_ascii_digit()) {
+                    // File descriptor of a redirection, e.g. `2>`
+                    return self.redirect(word);
+                }
+                break;
+            }
+            self.chars.next();
+            match c {
+                '\'' => self.single_quoted(&mut word),
+                '"' => self.double_quoted(&mut word),
+                '$' => self.dollar(&mut word, false),
+                '`' => self.substitution(&mut word, "`", '`', '`'),
+                '\\' => match self.chars.next() {
+                    Some('\n') => self.incomplete |= self.chars.peek().is_none(),
+                    Some(c) => word.push(c),
+                    None => {
+                        word.push('\\');
+                        self.incomplete = true;
+                    }
+                },
+                c => word.push(c),
+            }
+        }
+        if self.keep_spellings && has_expansion_chars(&word) {
+            let length = start.clone().count() - self.chars.clone().count();
+            return Token::Word(start.take(length).collect());
+        }
+        Token::Word(word)
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.skip_blanks();
+        let c = *self.chars.peek()?;
+        let token = match c {
+            '<' | '>' => {
+                let mut lookahead = self.chars.clone();
+                lookahead.next();
+                if lookahead.peek() == Some(&'(') {
+                    self.word()
+                } else {
+                    self.redirect(String::new())
+                }
+            }
+            '&' => {
+                self.chars.next();
+                if self.chars.peek() == Some(&'>') {
+                    self.redirect("&".to_string())
+                } else {
+                    self.control(c)
+                }
+            }
+            '\n' | '|' | ';' | '(' | ')' => {
+                self.chars.next();
+                if c == '\n' {
+                    self.skip_heredocs();
+                }
+                self.control(c)
+            }
+            _ => self.word(),
+        };
+
+        match &token {
+            // A here-string like `<<< word` is a word of its own rather than a delimiter
+            Token::Redirect(redirect)
+                if (redirect.ends_with("<<") && !redirect.ends_with("<<<"))
+                    || redirect.ends_with("<<-") =>
+            {
+                self.expecting_delimiter = Some(redirect.ends_with('-'));
+            }
+            Token::Word(word) => {
+                if let Some(strip_tabs) = self.expecting_delimiter.take() {
+                    self.heredocs.push((word.to_owned(), strip_tabs));
+                }
+            }
+            _ => self.expecting_delimiter = None,
+        }
+        Some(token)
+    }
+}
@@ -0,0 +1,218 @@
+use super::{is_incomplete, quote, split, split_commands, unquote};
+
+#[test]
+fn split_plain_words() {
+    assert_eq!(split("cargo build  --release"), vec!["cargo", "build", "--release"]);
+    assert_eq!(split("   "), Vec::<String>::new());
+}
+
+#[test]
+fn split_quoted_words() {
+    assert_eq!(
+        split(r#"git commit -m "fix bug""#),
+        vec!["git", "commit", "-m", "fix bug"]
+    );
+    assert_eq!(split("grep 'foo bar' src"), vec!["grep", "foo bar", "src"]);
+    assert_eq!(split(r#"echo "it's" 'say "hi"'"#), vec!["echo", "it's", r#"say "hi""#]);
+    assert_eq!(split(r#"echo a"b c"'d'"#), vec!["echo", "ab cd"]);
+    assert_eq!(split(r#"echo "" ''"#), vec!["echo", "", ""]);
+}
+
+#[test]
+fn split_escapes() {
+    assert_eq!(split(r"touch my\ file"), vec!["touch", "my file"]);
+    assert_eq!(split(r#"echo "a \"b\" c \d""#), vec!["echo", r#"a "b" c \d"#]);
+    assert_eq!(split(r"echo 'a\nb'"), vec!["echo", r"a\nb"]);
+    assert_eq!(split("cargo \\\n  test"), vec!["cargo", "test"]);
+}
+
+#[test]
+fn split_ansi_c_quotes() {
+    assert_eq!(split(r"printf $'a\tb\n'"), vec!["printf", "a\tb\n"]);
+    assert_eq!(split(r"echo $'it\'s' $'\x41\101'"), vec!["echo", "it's", "AA"]);
+    assert_eq!(split(r#"echo $"hi there""#), vec!["echo", "hi there"]);
+}
+
+#[test]
+fn split_expansions_and_comments() {
+    assert_eq!(split("ls $HOME/*.rs"), vec!["ls", "$HOME/*.rs"]);
+    assert_eq!(split("make # build it"), vec!["make"]);
+    assert_eq!(split("echo a#b"), vec!["echo", "a#b"]);
+}
+
+#[test]
+fn split_spelled_expansions() {
+    assert_eq!(split("echo '$HOME' $HOME"), vec!["echo", "'$HOME'", "$HOME"]);
+    assert_eq!(split(r"rm '*.rs' \*.rs *.rs"), vec!["rm", "'*.rs'", r"\*.rs", "*.rs"]);
+    assert_eq!(
+        split(r#"echo "$HOME/my dir" '{a,b}'"#),
+        vec!["echo", r#""$HOME/my dir""#, "'{a,b}'"]
+    );
+    assert_eq!(unquote("'$HOME'"), "$HOME");
+    assert_eq!(unquote(r#""$HOME/my dir""#), "$HOME/my dir");
+    assert_eq!(unquote("it's"), "it's");
+}
+
+#[test]
+fn split_unterminated_quotes() {
+    assert_eq!(split("echo 'abc"), vec!["echo", "abc"]);
+    assert_eq!(split(r#"echo "abc"#), vec!["echo", "abc"]);
+}
+
+#[test]
+fn split_operators() {
+    assert_eq!(
+        split("cargo fmt&&cargo test|tee log"),
+        vec!["cargo", "fmt", "&&", "cargo", "test", "|", "tee", "log"]
+    );
+    assert_eq!(split("make 2>&1 >out.txt"), vec!["make", "2>&1", ">", "out.txt"]);
+    assert_eq!(split("cat <<EOF"), vec!["cat", "<<", "EOF"]);
+    assert_eq!(split("cmd &>/dev/null &"), vec!["cmd", "&>", "/dev/null", "&"]);
+    assert_eq!(split(r"echo 'a|b' \;"), vec!["echo", "a|b", ";"]);
+}
+
+#[test]
+fn split_compound_commands() {
+    assert_eq!(
+        split_commands("cargo fmt && cargo test | tee log; git status"),
+        vec![
+            vec!["cargo", "fmt"],
+            vec!["cargo", "test"],
+            vec!["tee", "log"],
+            vec!["git", "status"],
+        ]
+    );
+    assert_eq!(
+        split_commands("make || (cd build && make) & echo started"),
+        vec![
+            vec!["make"],
+            vec!["cd", "build"],
+            vec!["make"],
+            vec!["echo", "started"],
+        ]
+    );
+    assert_eq!(
+        split_commands("ls\\\n -l\ngit status"),
+        vec![vec!["ls", "-l"], vec!["git", "status"]]
+    );
+}
+
+#[test]
+fn split_substitutions() {
+    assert_eq!(
+        split_commands("git checkout $(git branch --list | fzf)"),
+        vec![
+            vec!["git", "branch", "--list"],
+            vec!["fzf"],
+            vec!["git", "checkout", "$(git branch --list | fzf)"],
+        ]
+    );
+    assert_eq!(
+        split_commands(r#"echo "today is `date +%A`" $((1 + 2))"#),
+        vec![
+            vec!["date", "+%A"],
+            vec!["echo", r#""today is `date +%A`""#, "$((1 + 2))"],
+        ]
+    );
+    assert_eq!(
+        split_commands("diff <(sort a) <(sort b)"),
+        vec![
+            vec!["sort", "a"],
+            vec!["sort", "b"],
+            vec!["diff", "<(sort a)", "<(sort b)"],
+        ]
+    );
+    assert_eq!(split_commands("echo ${HOME:-/ home}"), vec![vec!["echo", "${HOME:-/ home}"]]);
+}
+
+#[test]
+fn split_reserved_words() {
+    assert_eq!(
+        split_commands("if test -f x; then rm x; fi"),
+        vec![vec!["test", "-f", "x"], vec!["rm", "x"]]
+    );
+    assert_eq!(
+        split_commands("for f in *; do echo $f; done"),
+        vec![vec!["echo", "$f"]]
+    );
+    assert_eq!(
+        split_commands("case $1 in a|b) make;; (*) echo no;; esac"),
+        vec![vec!["make"], vec!["echo", "no"]]
+    );
+    assert_eq!(split_commands("[[ -f x && -n $y ]] && rm x"), vec![vec!["rm", "x"]]);
+    assert_eq!(
+        split_commands("function hi { echo hi; }; select x in a b; do ls; done"),
+        vec![vec!["echo", "hi"], vec!["ls"]]
+    );
+    assert_eq!(split_commands("echo if for in"), vec![vec!["echo", "if", "for", "in"]]);
+}
+
+#[test]
+fn split_compound_redirections() {
+    assert_eq!(
+        split_commands("while read l; do echo $l; done < file.txt"),
+        vec![vec!["read", "l"], vec!["echo", "$l"]]
+    );
+    assert_eq!(
+        split_commands("{ make; make test; } > log 2>&1; ls"),
+        vec![vec!["make"], vec!["make", "test"], vec!["ls"]]
+    );
+    assert_eq!(split_commands("(cd src && make) >> log"), vec![vec!["cd", "src"], vec!["make"]]);
+    assert_eq!(split_commands("make > log 2>&1"), vec![vec!["make", ">", "log", "2>&1"]]);
+}
+
+#[test]
+fn split_heredocs() {
+    assert_eq!(
+        split_commands("cat <<EOF > notes\ngit status\nEOF\nls"),
+        vec![vec!["cat", "<<", "EOF", ">", "notes"], vec!["ls"]]
+    );
+    assert_eq!(
+        split_commands("cat <<-'END'; echo hi\n\tbody\n\tEND"),
+        vec![vec!["cat", "<<-", "END"], vec!["echo", "hi"]]
+    );
+    // Here-strings have no body
+    assert_eq!(
+        split_commands("cat <<< foo\necho hi\nls"),
+        vec![vec!["cat", "<<<", "foo"], vec!["echo", "hi"], vec!["ls"]]
+    );
+}
+
+#[test]
+fn incomplete_commands() {
+    assert!(!is_incomplete("cargo test"));
+    assert!(!is_incomplete("echo 'a b' \"c\" $(date)"));
+    assert!(!is_incomplete("cat <<EOF\nbody\nEOF"));
+    assert!(is_incomplete("echo 'abc"));
+    assert!(is_incomplete("echo \"abc"));
+    assert!(is_incomplete("echo $(date"));
+    assert!(is_incomplete("ls \\"));
+    assert!(is_incomplete("ls \\\n"));
+    assert!(is_incomplete("cat <<EOF"));
+    assert!(is_incomplete("cat <<EOF\nbody"));
+    assert!(!is_incomplete("cat <<< foo"));
+    assert!(!is_incomplete("read -r x <<< \"$HOME\""));
+    assert!(is_incomplete("make &&"));
+    assert!(is_incomplete("ls |"));
+    assert!(!is_incomplete("make &"));
+    assert!(is_incomplete("for f in *; do"));
+    assert!(is_incomplete("if true; then { echo a; }"));
+    assert!(!is_incomplete("case $1 in a) echo;; esac"));
+    assert!(!is_incomplete("echo do if {"));
+}
+
+#[test]
+fn quoting() {
+    assert_eq!(quote("--release"), "--release");
+    assert_eq!(quote("$HOME/*.rs"), "$HOME/*.rs");
+    assert_eq!(quote("'$HOME'"), "'$HOME'");
+    assert_eq!(quote("fix bug"), "'fix bug'");
+    assert_eq!(quote("it's"), r"'it'\''s'");
+    assert_eq!(quote(""), "''");
+    assert_eq!(quote("2>&1"), "2>&1");
+    assert_eq!(quote(">>"), ">>");
+    assert_eq!(quote("a>b"), "'a>b'");
+    for word in &["a|b", "a;b", "a&b", "(a)", r"a\b", "'$HOME'", "~/'my dir'"] {
+        assert_eq!(split(&quote(word)), vec![word.to_string()]);
+    }
+}
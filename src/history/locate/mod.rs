@@ -0,0 +1,98 @@
+use std::env;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use super::Format;
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// The parts of a user's environment that determine where their history and startup files are
+#[derive(Debug, Default)]
+pub struct Environment {
+    /// Value of `$HISTFILE`, which shells rarely export to child processes
+    pub histfile: Option<PathBuf>,
+    /// Value of `$SHELL`, the user's login shell
+    pub shell: Option<PathBuf>,
+    /// Value of `$HOME`
+    pub home: Option<PathBuf>,
+    /// Value of `$XDG_DATA_HOME`, under which fish stores its history
+    pub data_home: Option<PathBuf>,
+    /// Value of `$XDG_CONFIG_HOME`, under which fish stores its configuration
+    pub config_home: Option<PathBuf>,
+    /// Value of `$ZDOTDIR`, where zsh looks for its startup files
+    pub zdotdir: Option<PathBuf>,
+}
+
+impl Environment {
+    /// Reads the environment of the current process
+    pub fn current() -> Self {
+        Environment {
+            histfile: env::var_os("HISTFILE").map(PathBuf::from),
+            shell: env::var_os("SHELL").map(PathBuf::from),
+            home: env::var_os("HOME").map(PathBuf::from),
+            data_home: env::var_os("XDG_DATA_HOME").map(PathBuf::from),
+            config_home: env::var_os("XDG_CONFIG_HOME").map(PathBuf::from),
+            zdotdir: env::var_os("ZDOTDIR").map(PathBuf::from),
+        }
+    }
+
+    /// Determines the format of the login shell's history
+    fn shell_format(&self) -> Option<Format> {
+        self.shell
+            .as_ref()
+            .and_then(|shell| shell.file_name())
+            .and_then(OsStr::to_str)
+            .and_then(|name| name.parse().ok())
+            .filter(|&format| format != Format::Plain)
+    }
+
+    /// Produces the default location of a shell's history file
+    fn default_file(&self, format: Format) -> Option<PathBuf> {
+        let home = self.home.as_ref();
+        match format {
+            Format::Zsh => home.map(|home| home.join(".zsh_history")),
+            Format::Bash => home.map(|home| home.join(".bash_history")),
+            Format::Fish => self
+                .data_home
+                .clone()
+                .or_else(|| home.map(|home| home.join(".local").join("share")))
+                .map(|data| data.join("fish").join("fish_history")),
+            Format::Plain => None,
+        }
+    }
+}
+
+/// Finds the user's history file and its format
+///
+/// `$HISTFILE` is used if it is set, followed by the default history file of the login shell and
+/// finally the default history file of any shell that exists. The format is `None` when it
+/// can't be told from the environment and should be detected from the file's contents.
+///
+/// Since `$HISTFILE` may have been inherited from a shell other than the login shell, its format
+/// is only guessed from its name, never from `$SHELL`.
+pub fn locate(env: &Environment) -> Option<(PathBuf, Option<Format>)> {
+    if let Some(histfile) = env.histfile.as_ref().filter(|path| path.is_file()) {
+        return Some((histfile.to_owned(), format_of_file_name(histfile)));
+    }
+
+    let shells = env
+        .shell_format()
+        .into_iter()
+        .chain(vec![Format::Zsh, Format::Bash, Format::Fish]);
+    for format in shells {
+        if let Some(path) = env.default_file(format).filter(|path| path.is_file()) {
+            return Some((path, Some(format)));
+        }
+    }
+    None
+}
+
+/// Guesses the format of a history file from its name, e.g. `.zsh_history`
+fn format_of_file_name(path: &Path) -> Option<Format> {
+    let name = path.file_name()?.to_str()?;
+    vec![Format::Zsh, Format::Bash, Format::Fish]
+        .into_iter()
+        .find(|format| name.contains(&format.to_string()))
+}
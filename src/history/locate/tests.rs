@@ -0,0 +1,82 @@
+use std::fs::{create_dir_all, File};
+use std::path::PathBuf;
+
+use tempfile::{tempdir, TempDir};
+
+use super::{Environment, locate};
+use crate::history::Format;
+
+fn home_with(files: &[&str]) -> TempDir {
+    let home = tempdir().unwrap();
+    for file in files {
+        let path = home.path().join(file);
+        create_dir_all(path.parent().unwrap()).unwrap();
+        File::create(path).unwrap();
+    }
+    home
+}
+
+fn environment(home: &TempDir, shell: Option<&str>) -> Environment {
+    Environment {
+        shell: shell.map(PathBuf::from),
+        home: Some(home.path().to_owned()),
+        ..Environment::default()
+    }
+}
+
+#[test]
+fn locate_histfile() {
+    let home = home_with(&["history", ".bash_history"]);
+    let env = Environment {
+        histfile: Some(home.path().join("history")),
+        ..environment(&home, Some("/usr/bin/zsh"))
+    };
+    // The login shell needn't be the shell that set `$HISTFILE`, so the format is detected later
+    assert_eq!(locate(&env), Some((home.path().join("history"), None)));
+
+    let env = Environment {
+        histfile: Some(home.path().join(".bash_history")),
+        ..env
+    };
+    assert_eq!(locate(&env), Some((home.path().join(".bash_history"), Some(Format::Bash))));
+}
+
+#[test]
+fn locate_missing_histfile() {
+    let home = home_with(&[".bash_history"]);
+    let env = Environment {
+        histfile: Some(home.path().join("missing")),
+        ..environment(&home, Some("/bin/bash"))
+    };
+    assert_eq!(locate(&env), Some((home.path().join(".bash_history"), Some(Format::Bash))));
+}
+
+#[test]
+fn locate_login_shell_history() {
+    let home = home_with(&[".zsh_history", ".bash_history", ".local/share/fish/fish_history"]);
+    let found = |shell| locate(&environment(&home, Some(shell)));
+    let expected = |file, format| Some((home.path().join(file), Some(format)));
+
+    assert_eq!(found("/bin/bash"), expected(".bash_history", Format::Bash));
+    assert_eq!(found("/bin/zsh"), expected(".zsh_history", Format::Zsh));
+    assert_eq!(
+        found("/usr/local/bin/fish"),
+        expected(".local/share/fish/fish_history", Format::Fish)
+    );
+}
+
+#[test]
+fn locate_well_known_history() {
+    let home = home_with(&[".local/share/fish/fish_history"]);
+    let env = environment(&home, Some("/bin/tcsh"));
+    assert_eq!(
+        locate(&env),
+        Some((home.path().join(".local/share/fish/fish_history"), Some(Format::Fish)))
+    );
+
+    let env = Environment {
+        data_home: Some(home.path().join("data")),
+        ..env
+    };
+    assert_eq!(locate(&env), None);
+}
@@ -1,42 +1,147 @@
+#[cfg(test)]
 use std::{
     fs::File,
-    io::{BufRead, BufReader},
+    io::{self, BufReader, Read},
     iter::FromIterator,
 };
 
-use regex::Regex;
-
+pub use bash::BashEntries;
+pub use diagnostic::{Diagnostic, Diagnostics, Reason};
+pub use entry::Entry;
 pub use executed_command::ExecutedCommand;
+pub use fish::FishEntries;
+pub use format::Format;
+pub use lexer::{is_incomplete, quote, split_commands, unquote};
+pub use locate::{Environment, locate};
+pub use stream::CommandStream;
+pub use zsh::ZshEntries;
 
+mod bash;
+mod diagnostic;
+mod entry;
 mod executed_command;
+mod fish;
+mod format;
+mod lexer;
+mod locate;
+mod stream;
+mod timestamp;
+mod zsh;
 #[cfg(test)]
 #[cfg_attr(tarpaulin, skip)]
 mod tests;
 
-/// Represents a history file
-#[derive(Debug, PartialEq)]
+/// Represents a history file read into memory
+///
+/// Only tests read whole histories. Sub-commands stream history files through a `CommandStream`
+/// instead, so that they needn't fit in memory.
+#[cfg(test)]
+#[derive(Debug, Default, PartialEq)]
 pub struct History {
     pub commands: Vec<ExecutedCommand>,
+    /// Summary of the lines that were parsed or skipped
+    pub diagnostics: Diagnostics,
 }
 
-impl<T: AsRef<str>> FromIterator<T> for History {
-    /// Parses commands from an iterator, discarding any lines that can't be parsed
-    fn from_iter<I: IntoIterator<Item=T>>(lines: I) -> Self {
-        let re = Regex::new(ExecutedCommand::PATTERN).unwrap();
-        let lines = lines.into_iter();
-        let commands = lines.filter_map(|line| ExecutedCommand::try_parse(line.as_ref(), &re));
-        History {
-            commands: commands.collect(),
+/// Options controlling how history files are parsed
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ParseOptions {
+    /// Format of the history file, detected from its contents if `None`
+    pub format: Option<Format>,
+    /// Whether compound command lines are kept whole rather than split into individual commands
+    pub whole_lines: bool,
+    /// Whether the paths fish records with each command are kept
+    pub fish_paths: bool,
+}
+
+#[cfg(test)]
+impl History {
+    /// Parses commands from the lines of a history file
+    ///
+    /// Lines that can't be parsed are skipped and recorded in the history's `diagnostics`.
+    pub fn parse<I, T>(lines: I, options: ParseOptions) -> Self
+        where
+            I: IntoIterator<Item=T>,
+            T: AsRef<str>,
+    {
+        let lines: Vec<T> = lines.into_iter().collect();
+        let format = options.format.unwrap_or_else(|| Format::detect(&lines));
+        let mut history = History {
+            diagnostics: Diagnostics {
+                lines: lines.len(),
+                ..Diagnostics::default()
+            },
+            ..History::default()
+        };
+        for entry in entries(lines.into_iter(), format, options.fish_paths) {
+            match entry {
+                Ok(entry) => {
+                    history.diagnostics.parsed += 1;
+                    history.commands.append(&mut entry.into_commands(options.whole_lines));
+                }
+                Err(diagnostic) => history.diagnostics.skipped.push(diagnostic),
+            }
+        }
+        history
+    }
+
+    /// Reads and parses lines from a history file
+    ///
+    /// Use a `CommandStream` instead to process the commands without keeping them in memory.
+    pub fn read<R: Read>(reader: R, options: ParseOptions) -> io::Result<Self> {
+        let mut history = History::default();
+        let mut stream = CommandStream::new(BufReader::new(reader), options);
+        for command in stream.by_ref() {
+            match command {
+                Ok(command) => history.commands.push(command),
+                Err(diagnostic) => history.diagnostics.skipped.push(diagnostic),
+            }
         }
+        history.diagnostics.parsed = stream.parsed();
+        history.diagnostics.lines = stream.finish()?;
+        Ok(history)
     }
 }
 
+/// An entry of a history file, or the reason it couldn't be parsed
+type ParsedEntry = Result<Entry, Diagnostic>;
+
+/// Produces an iterator of the entries parsed from lines of a history file
+///
+/// Lines that can't be parsed are produced as `Diagnostic`s, and lines continuing a multi-line
+/// command are joined into a single entry. The paths of fish entries are kept if `fish_paths`
+/// is set.
+fn entries<'a, I, T>(
+    lines: I,
+    format: Format,
+    fish_paths: bool,
+) -> Box<dyn Iterator<Item=ParsedEntry> + 'a>
+    where
+        I: Iterator<Item=T> + 'a,
+        T: AsRef<str> + 'a,
+{
+    match format {
+        Format::Zsh => Box::new(ZshEntries::new(lines)),
+        Format::Bash => Box::new(BashEntries::new(lines)),
+        Format::Fish => Box::new(FishEntries::new(lines, fish_paths)),
+        Format::Plain => Box::new(lines.map(|line| Ok(Entry::new(line.as_ref(), None)))),
+    }
+}
+
+#[cfg(test)]
 impl From<File> for History {
-    /// Attempts to read and parse lines from a history file
+    /// Attempts to read and parse lines from a history file, detecting its format
+    ///
+    /// A file that can't be read produces an empty history.
     fn from(file: File) -> Self {
-        BufReader::new(file)
-            .lines()
-            .filter_map(Result::ok)
-            .collect()
+        History::read(file, ParseOptions::default()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+impl<T: AsRef<str>> FromIterator<T> for History {
+    /// Parses commands from an iterator, detecting the format of the lines
+    fn from_iter<I: IntoIterator<Item=T>>(lines: I) -> Self {
+        History::parse(lines, ParseOptions::default())
     }
 }
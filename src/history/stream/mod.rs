@@ -0,0 +1,107 @@
+use std::{
+    cell::RefCell,
+    io::{self, BufRead},
+    rc::Rc,
+    vec,
+};
+
+use super::{Diagnostic, entries, ExecutedCommand, Format, ParsedEntry, ParseOptions};
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Progress of reading the lines of a history file
+#[derive(Debug, Default)]
+struct ReadState {
+    /// Number of lines read so far
+    lines: usize,
+    /// Error that stopped reading, if any
+    error: Option<io::Error>,
+}
+
+/// Iterator over the commands of a history file, parsed as its lines are read
+///
+/// Only the lines needed to detect the format of the file are buffered, so histories of any size
+/// are parsed in bounded memory. Lines that can't be parsed are produced as `Diagnostic`s.
+pub struct CommandStream<'a> {
+    entries: Box<dyn Iterator<Item=ParsedEntry> + 'a>,
+    commands: vec::IntoIter<ExecutedCommand>,
+    whole_lines: bool,
+    /// Number of entries parsed so far
+    parsed: usize,
+    state: Rc<RefCell<ReadState>>,
+}
+
+impl<'a> CommandStream<'a> {
+    /// Initializes a `CommandStream` reading lines from `reader`
+    ///
+    /// Lines are decoded according to the history file's format, replacing any bytes that are
+    /// not valid UTF-8 rather than discarding the line.
+    pub fn new<R: BufRead + 'a>(reader: R, options: ParseOptions) -> Self {
+        let state = Rc::new(RefCell::new(ReadState::default()));
+        let line_state = Rc::clone(&state);
+        let mut lines = reader.split(b'\n').map_while(move |line| {
+            let mut state = line_state.borrow_mut();
+            match line {
+                Ok(mut line) => {
+                    if line.last() == Some(&b'\r') {
+                        line.pop();
+                    }
+                    state.lines += 1;
+                    Some(line)
+                }
+                Err(error) => {
+                    state.error = Some(error);
+                    None
+                }
+            }
+        });
+
+        let head: Vec<Vec<u8>> = lines.by_ref().take(Format::SNIFF_LINES).collect();
+        let format = options.format.unwrap_or_else(|| {
+            Format::detect(head.iter().map(|line| String::from_utf8_lossy(line)))
+        });
+        let lines = head.into_iter().chain(lines).map(move |line| format.decode(&line));
+        CommandStream {
+            entries: entries(lines, format, options.fish_paths),
+            commands: Vec::new().into_iter(),
+            whole_lines: options.whole_lines,
+            parsed: 0,
+            state,
+        }
+    }
+
+    /// Produces the number of entries parsed so far, each of which may span several lines
+    pub fn parsed(&self) -> usize {
+        self.parsed
+    }
+
+    /// Consumes the stream, producing the number of lines read or the error that stopped reading
+    pub fn finish(self) -> io::Result<usize> {
+        let mut state = self.state.borrow_mut();
+        match state.error.take() {
+            Some(error) => Err(error),
+            None => Ok(state.lines),
+        }
+    }
+}
+
+impl<'a> Iterator for CommandStream<'a> {
+    type Item = Result<ExecutedCommand, Diagnostic>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(command) = self.commands.next() {
+                return Some(Ok(command));
+            }
+            match self.entries.next()? {
+                Ok(entry) => {
+                    self.parsed += 1;
+                    self.commands = entry.into_commands(self.whole_lines).into_iter();
+                }
+                Err(diagnostic) => return Some(Err(diagnostic)),
+            }
+        }
+    }
+}
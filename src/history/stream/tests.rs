@@ -0,0 +1,65 @@
+use std::io::{self, BufReader, Read};
+
+use super::CommandStream;
+use crate::history::{Diagnostic, ParseOptions, Reason};
+use crate::testing::at;
+
+/// A reader that fails after producing its contents
+struct FailingReader<'a>(&'a [u8]);
+
+impl<'a> Read for FailingReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0.is_empty() {
+            return Err(io::Error::other("disconnected"));
+        }
+        self.0.read(buf)
+    }
+}
+
+fn args(stream: &mut CommandStream) -> Vec<Vec<String>> {
+    stream.map(|command| command.unwrap().args).collect()
+}
+
+#[test]
+fn stream_commands() {
+    let input = &b": 1556993411:0;cargo fmt && cargo test\n: 1556993420:3;ls caf\xc3\x83\x89\n"[..];
+    let mut stream = CommandStream::new(input, ParseOptions::default());
+    assert_eq!(
+        args(&mut stream),
+        vec![vec!["cargo", "fmt"], vec!["cargo", "test"], vec!["ls", "caf\u{e9}"]]
+    );
+    assert_eq!(stream.finish().unwrap(), 2);
+}
+
+#[test]
+fn stream_past_detected_lines() {
+    let input: String = (0..120).map(|i| format!("#{}\necho {}\n", 1556993411 + i, i)).collect();
+    let mut stream = CommandStream::new(input.as_bytes(), ParseOptions::default());
+    let commands: Vec<_> = stream.by_ref().map(Result::unwrap).collect();
+    assert_eq!(commands.len(), 120);
+    assert_eq!(commands[119].args, vec!["echo", "119"]);
+    assert_eq!(commands[119].time, at(1556993530));
+    assert_eq!(stream.finish().unwrap(), 240);
+}
+
+#[test]
+fn stream_diagnostics() {
+    let input = &b"#1556993411\n#1556993420\ncargo fmt\n"[..];
+    let mut stream = CommandStream::new(input, ParseOptions::default());
+    let results: Vec<_> = stream.by_ref().collect();
+    assert_eq!(stream.parsed(), 1);
+    assert_eq!(results.len(), 2);
+    assert_eq!(
+        results[0],
+        Err(Diagnostic::new(1, Reason::MissingCommand, "#1556993411"))
+    );
+    assert_eq!(results[1].as_ref().unwrap().args, vec!["cargo", "fmt"]);
+}
+
+#[test]
+fn stream_read_error() {
+    let reader = BufReader::new(FailingReader(b"cargo fmt\ncargo test\n"));
+    let mut stream = CommandStream::new(reader, ParseOptions::default());
+    assert_eq!(args(&mut stream), vec![vec!["cargo", "fmt"], vec!["cargo", "test"]]);
+    assert_eq!(stream.finish().unwrap_err().to_string(), "disconnected");
+}
@@ -1,8 +1,18 @@
 use std::{io::{Seek, SeekFrom, Write}, iter::FromIterator};
 
+use chrono::{DateTime, Utc};
 use tempfile::tempfile;
 
-use super::{ExecutedCommand, History};
+use crate::testing::{at, strings};
+
+use super::{Diagnostic, ExecutedCommand, Format, History, ParseOptions, Reason};
+
+fn options(format: Format) -> ParseOptions {
+    ParseOptions {
+        format: Some(format),
+        ..ParseOptions::default()
+    }
+}
 
 #[test]
 fn history_dated_format() {
@@ -13,7 +23,9 @@ fn history_dated_format() {
     let expected = vec![
         ExecutedCommand {
             args: vec!["cargo".to_string(), "fmt".to_string()],
-            time: Some(1556993411),
+            time: at(1556993411),
+            duration: Some(0),
+            paths: Vec::new(),
         },
         ExecutedCommand {
             args: vec![
@@ -21,7 +33,9 @@ fn history_dated_format() {
                 "build".to_string(),
                 "--release".to_string(),
             ],
-            time: Some(1556991281),
+            time: at(1556991281),
+            duration: Some(0),
+            paths: Vec::new(),
         },
     ];
 
@@ -36,6 +50,8 @@ fn history_undated_format() {
         ExecutedCommand {
             args: vec!["cargo".to_string(), "fmt".to_string()],
             time: None,
+            duration: None,
+            paths: Vec::new(),
         },
         ExecutedCommand {
             args: vec![
@@ -44,6 +60,46 @@ fn history_undated_format() {
                 "--release".to_string(),
             ],
             time: None,
+            duration: None,
+            paths: Vec::new(),
+        },
+    ];
+
+    let hist = History::from_iter(input);
+    assert_eq!(hist.commands, expected);
+}
+
+#[test]
+fn history_bash_timestamp_format() {
+    let input = vec![
+        "#1556993411",
+        "cargo fmt",
+        "cargo build --release",
+        "#1556991281",
+        "cargo test",
+    ];
+    let expected = vec![
+        ExecutedCommand {
+            args: vec!["cargo".to_string(), "fmt".to_string()],
+            time: at(1556993411),
+            duration: None,
+            paths: Vec::new(),
+        },
+        ExecutedCommand {
+            args: vec![
+                "cargo".to_string(),
+                "build".to_string(),
+                "--release".to_string(),
+            ],
+            time: None,
+            duration: None,
+            paths: Vec::new(),
+        },
+        ExecutedCommand {
+            args: vec!["cargo".to_string(), "test".to_string()],
+            time: at(1556991281),
+            duration: None,
+            paths: Vec::new(),
         },
     ];
 
@@ -61,13 +117,212 @@ fn history_from_file() {
         ExecutedCommand {
             args: vec!["cargo".to_string(), "test".to_string()],
             time: None,
+            duration: None,
+            paths: Vec::new(),
         },
         ExecutedCommand {
             args: vec!["cargo".to_string(), "run".to_string()],
             time: None,
+            duration: None,
+            paths: Vec::new(),
         },
     ];
 
     let hist = History::from(f);
     assert_eq!(hist.commands, expected);
 }
+
+#[test]
+fn history_fish_format() {
+    let input = vec![
+        "- cmd: cargo fmt",
+        "  when: 1556993411",
+        "- cmd: cargo build --release",
+        "  when: 1556991281",
+        "  paths:",
+        "    - --release",
+    ];
+    let expected = vec![
+        ExecutedCommand {
+            args: vec!["cargo".to_string(), "fmt".to_string()],
+            time: at(1556993411),
+            duration: None,
+            paths: Vec::new(),
+        },
+        ExecutedCommand {
+            args: vec![
+                "cargo".to_string(),
+                "build".to_string(),
+                "--release".to_string(),
+            ],
+            time: at(1556991281),
+            duration: None,
+            paths: Vec::new(),
+        },
+    ];
+
+    let hist = History::parse(input, options(Format::Fish));
+    assert_eq!(hist.commands, expected);
+}
+
+#[test]
+fn history_from_fish_file() {
+    let mut f = tempfile().unwrap();
+    write!(f, "- cmd: cargo test\n  when: 1556993411\n").unwrap();
+    f.seek(SeekFrom::Start(0)).unwrap();
+
+    let expected = vec![ExecutedCommand {
+        args: vec!["cargo".to_string(), "test".to_string()],
+        time: at(1556993411),
+        duration: None,
+        paths: Vec::new(),
+    }];
+
+    let hist = History::read(f, ParseOptions::default()).unwrap();
+    assert_eq!(hist.commands, expected);
+}
+
+#[test]
+fn history_forced_format() {
+    // A plain history whose first command happens to look like a bash timestamp
+    let input = vec!["#1556993411", "cargo fmt"];
+    let hist = History::parse(input, options(Format::Plain));
+    assert_eq!(hist.commands.last().map(|command| command.time), Some(None));
+}
+
+#[test]
+fn history_plain_comments() {
+    // Lines resembling bash timestamps are comments, so they aren't commands in a plain history
+    let input = vec!["#1556993411", "cargo fmt", "# cargo test"];
+    let hist = History::parse(input, options(Format::Plain));
+    let args: Vec<Vec<String>> = hist.commands.into_iter().map(|command| command.args).collect();
+    assert_eq!(args, vec![vec!["cargo", "fmt"]]);
+}
+
+#[test]
+fn history_compound_commands() {
+    let input = vec![": 1556993411:0;cargo fmt && cargo test | tee log; git status"];
+    let args: Vec<Vec<String>> = History::from_iter(&input)
+        .commands
+        .into_iter()
+        .map(|command| command.args)
+        .collect();
+    assert_eq!(
+        args,
+        vec![
+            vec!["cargo", "fmt"],
+            vec!["cargo", "test"],
+            vec!["tee", "log"],
+            vec!["git", "status"],
+        ]
+    );
+
+    let whole_lines = ParseOptions {
+        whole_lines: true,
+        ..ParseOptions::default()
+    };
+    let hist = History::parse(&input, whole_lines);
+    assert_eq!(hist.commands.len(), 1);
+    assert_eq!(hist.commands[0].time, at(1556993411));
+}
+
+#[test]
+fn history_multi_line_commands() {
+    let input = vec![
+        ": 1556993411:0;cat <<EOF | wc -l\\",
+        "cargo test\\",
+        "EOF",
+        ": 1556993420:0;cargo build \\\\",
+        "  --release",
+    ];
+    let args: Vec<Vec<String>> = History::from_iter(input)
+        .commands
+        .into_iter()
+        .map(|command| command.args)
+        .collect();
+    assert_eq!(
+        args,
+        vec![
+            vec!["cat", "<<", "EOF"],
+            vec!["wc", "-l"],
+            vec!["cargo", "build", "--release"],
+        ]
+    );
+}
+
+#[test]
+fn history_from_non_utf8_file() {
+    let mut f = tempfile().unwrap();
+    f.write_all(b"cargo test\nls caf\xe9\ncargo run\n").unwrap();
+    f.seek(SeekFrom::Start(0)).unwrap();
+
+    let hist = History::read(f, ParseOptions::default()).unwrap();
+    let args: Vec<Vec<String>> = hist.commands.into_iter().map(|command| command.args).collect();
+    assert_eq!(
+        args,
+        vec![
+            vec!["cargo", "test"],
+            vec!["ls", "caf\u{fffd}"],
+            vec!["cargo", "run"],
+        ]
+    );
+}
+
+#[test]
+fn history_from_metafied_zsh_file() {
+    // Zsh writes "é" (0xc3 0xa9) as 0xc3 followed by the meta character 0x83 and 0xa9 ^ 32
+    let mut f = tempfile().unwrap();
+    f.write_all(b": 1556993411:0;ls caf\xc3\x83\x89\r\n").unwrap();
+    f.seek(SeekFrom::Start(0)).unwrap();
+
+    let hist = History::read(f, ParseOptions::default()).unwrap();
+    assert_eq!(
+        hist.commands,
+        vec![ExecutedCommand {
+            args: vec!["ls".to_string(), "caf\u{e9}".to_string()],
+            time: at(1556993411),
+            duration: Some(0),
+            paths: Vec::new(),
+        }]
+    );
+}
+
+#[test]
+fn history_diagnostics() {
+    let input = vec!["#1556993411", "cargo fmt", "#1556993420", "#1556993430", "cargo test"];
+    let hist = History::parse(input, options(Format::Bash));
+    assert_eq!(hist.commands.len(), 2);
+    assert_eq!(hist.diagnostics.lines, 5);
+    assert_eq!(hist.diagnostics.parsed, 2);
+    assert_eq!(
+        hist.diagnostics.skipped,
+        vec![Diagnostic::new(3, Reason::MissingCommand, "#1556993420")]
+    );
+}
+
+#[test]
+fn history_bash_multi_line_commands() {
+    let input = vec![
+        "#1556993411",
+        "for f in *.rs; do",
+        "  rustfmt $f",
+        "done",
+        "cargo test",
+        "#1556993420",
+        "echo 'a",
+        "b'",
+    ];
+    let commands: Vec<(Vec<String>, Option<DateTime<Utc>>)> = History::from_iter(input)
+        .commands
+        .into_iter()
+        .map(|command| (command.args, command.time))
+        .collect();
+    assert_eq!(
+        commands,
+        vec![
+            (strings(&["rustfmt", "$f"]), at(1556993411)),
+            (strings(&["cargo", "test"]), None),
+            (strings(&["echo", "a\nb"]), at(1556993420)),
+        ]
+    );
+}
@@ -0,0 +1,83 @@
+use chrono::{DateTime, TimeZone, Utc};
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+/// Units in which a timestamp counts the time since the Unix epoch
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Precision {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+}
+
+impl Precision {
+    /// Guesses the precision of a timestamp from its number of digits
+    ///
+    /// Timestamps of up to 11 digits are taken to be seconds, which covers times until the year
+    /// 5138, and every 3 digits more are taken to be a finer unit.
+    pub fn from_digits(digits: usize) -> Self {
+        match digits {
+            0..=11 => Precision::Seconds,
+            12..=14 => Precision::Milliseconds,
+            15..=17 => Precision::Microseconds,
+            _ => Precision::Nanoseconds,
+        }
+    }
+
+    /// Number of nanoseconds in one unit of the precision
+    fn nanos(self) -> i64 {
+        match self {
+            Precision::Seconds => NANOS_PER_SECOND,
+            Precision::Milliseconds => 1_000_000,
+            Precision::Microseconds => 1_000,
+            Precision::Nanoseconds => 1,
+        }
+    }
+}
+
+/// Parses a whole number of `precision` units since the Unix epoch, e.g. `1556993411000`
+pub fn parse_units(s: &str, precision: Precision) -> Option<DateTime<Utc>> {
+    if !is_digits(s) {
+        return None;
+    }
+    let units: i64 = s.parse().ok()?;
+    let per_second = NANOS_PER_SECOND / precision.nanos();
+    let nanos = units % per_second * precision.nanos();
+    Utc.timestamp_opt(units / per_second, nanos as u32).single()
+}
+
+/// Parses a number of seconds since the Unix epoch with an optional fraction, e.g.
+/// `1556993411.25`
+///
+/// Digits of the fraction beyond nanoseconds are ignored.
+pub fn parse_seconds(s: &str) -> Option<DateTime<Utc>> {
+    let (seconds, fraction) = match s.find('.') {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, "0"),
+    };
+    if !is_digits(seconds) || !is_digits(fraction) {
+        return None;
+    }
+    let fraction = fraction.get(..9).unwrap_or(fraction);
+    let nanos = format!("{:0<9}", fraction).parse().ok()?;
+    Utc.timestamp_opt(seconds.parse().ok()?, nanos).single()
+}
+
+/// Parses a timestamp, inferring the precision of whole numbers from their number of digits
+pub fn parse(s: &str) -> Option<DateTime<Utc>> {
+    if s.contains('.') {
+        parse_seconds(s)
+    } else {
+        parse_units(s, Precision::from_digits(s.len()))
+    }
+}
+
+/// Determines whether a string is made up of one or more ASCII digits
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
@@ -0,0 +1,47 @@
+use chrono::{TimeZone, Utc};
+
+use super::{parse, parse_seconds, parse_units, Precision};
+
+#[test]
+fn precision_from_digits() {
+    assert_eq!(Precision::from_digits(9), Precision::Seconds);
+    assert_eq!(Precision::from_digits(10), Precision::Seconds);
+    assert_eq!(Precision::from_digits(13), Precision::Milliseconds);
+    assert_eq!(Precision::from_digits(16), Precision::Microseconds);
+    assert_eq!(Precision::from_digits(19), Precision::Nanoseconds);
+}
+
+#[test]
+fn parsing_units() {
+    let time = Utc.timestamp(1556993411, 250_000_000);
+    assert_eq!(parse_units("1556993411250", Precision::Milliseconds), Some(time));
+    assert_eq!(parse_units("1556993411250000", Precision::Microseconds), Some(time));
+    assert_eq!(parse_units("1556993411250000000", Precision::Nanoseconds), Some(time));
+    assert_eq!(parse_units("1556993411", Precision::Seconds), Some(Utc.timestamp(1556993411, 0)));
+    assert_eq!(parse_units("-1", Precision::Seconds), None);
+    assert_eq!(parse_units("", Precision::Seconds), None);
+}
+
+#[test]
+fn parsing_seconds() {
+    assert_eq!(parse_seconds("1556993411"), Some(Utc.timestamp(1556993411, 0)));
+    assert_eq!(parse_seconds("1556993411.25"), Some(Utc.timestamp(1556993411, 250_000_000)));
+    assert_eq!(
+        parse_seconds("1556993411.1234567891"),
+        Some(Utc.timestamp(1556993411, 123_456_789))
+    );
+    assert_eq!(parse_seconds("1556993411."), None);
+    assert_eq!(parse_seconds(".25"), None);
+}
+
+#[test]
+fn parsing_any_precision() {
+    assert_eq!(parse("1556993411"), Some(Utc.timestamp(1556993411, 0)));
+    assert_eq!(parse("1556993411.5"), Some(Utc.timestamp(1556993411, 500_000_000)));
+    assert_eq!(parse("1556993411500"), Some(Utc.timestamp(1556993411, 500_000_000)));
+    // Times before 2001 and after 2106
+    assert_eq!(parse("946684799"), Some(Utc.ymd(1999, 12, 31).and_hms(23, 59, 59)));
+    assert_eq!(parse("4354819200"), Some(Utc.ymd(2108, 1, 1).and_hms(0, 0, 0)));
+    // Too large to be any time
+    assert_eq!(parse("99999999999999999999"), None);
+}
@@ -0,0 +1,96 @@
+use regex::Regex;
+
+use super::{Diagnostic, Entry, Reason};
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Byte zsh writes before bytes that it "metafies" in history files
+const META: u8 = 0x83;
+
+/// Regex pattern for lines that start like an extended history entry, valid or not
+const HEADER_PATTERN: &str = r"^: \d[\d.:]*;";
+
+/// Reverses the metafication zsh applies to special bytes in history files
+///
+/// Zsh writes NUL and the bytes from `META` to 0xA2, which it uses as tokens internally, as
+/// `META` followed by the original byte XORed with 32. Other bytes are written as-is.
+pub fn unmetafy(bytes: &[u8]) -> Vec<u8> {
+    let mut unmetafied = Vec::with_capacity(bytes.len());
+    let mut bytes = bytes.iter();
+    while let Some(&byte) = bytes.next() {
+        if byte == META {
+            if let Some(&next) = bytes.next() {
+                unmetafied.push(next ^ 32);
+            }
+        } else {
+            unmetafied.push(byte);
+        }
+    }
+    unmetafied
+}
+
+/// Iterator over the entries of a zsh history file
+///
+/// Zsh writes a backslash before each newline of a multi-line command, so lines ending in a
+/// backslash are joined with the line that follows them. Entries whose extended history header
+/// can't be parsed are produced as `Diagnostic`s.
+pub struct ZshEntries<I: Iterator> {
+    lines: I,
+    re: Regex,
+    header_re: Regex,
+    line_number: usize,
+}
+
+impl<I, T> ZshEntries<I>
+    where
+        I: Iterator<Item=T>,
+        T: AsRef<str>,
+{
+    /// Initializes a `ZshEntries` iterator
+    pub fn new(lines: I) -> Self {
+        ZshEntries {
+            lines,
+            re: Regex::new(Entry::PATTERN).unwrap(),
+            header_re: Regex::new(HEADER_PATTERN).unwrap(),
+            line_number: 0,
+        }
+    }
+
+    /// Consumes the next line, keeping track of its line number
+    fn next_line(&mut self) -> Option<T> {
+        let line = self.lines.next()?;
+        self.line_number += 1;
+        Some(line)
+    }
+}
+
+impl<I, T> Iterator for ZshEntries<I>
+    where
+        I: Iterator<Item=T>,
+        T: AsRef<str>,
+{
+    type Item = Result<Entry, Diagnostic>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = self.next_line()?.as_ref().to_string();
+        let line_number = self.line_number;
+        while line.ends_with('\\') {
+            match self.next_line() {
+                Some(next) => {
+                    line.pop();
+                    line.push('\n');
+                    line.push_str(next.as_ref());
+                }
+                None => break,
+            }
+        }
+        match Entry::try_parse(&line, &self.re) {
+            Some(entry) if entry.time.is_some() || !self.header_re.is_match(&line) => {
+                Some(Ok(entry))
+            }
+            _ => Some(Err(Diagnostic::new(line_number, Reason::InvalidTimestamp, &line))),
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{TimeZone, Utc};
+
+use super::{unmetafy, ZshEntries};
+use crate::history::{Diagnostic, Entry, Reason};
+use crate::testing::at;
+
+fn timed(cmd: &str, time: i64, duration: u32) -> Entry {
+    Entry {
+        duration: Some(duration),
+        ..Entry::new(cmd, at(time))
+    }
+}
+
+#[test]
+fn single_line_entries() {
+    let lines = vec![": 1556993411:0;cargo fmt", "cargo test"];
+    let entries: Vec<Entry> = ZshEntries::new(lines.into_iter()).map(Result::unwrap).collect();
+    assert_eq!(
+        entries,
+        vec![
+            timed("cargo fmt", 1556993411, 0),
+            Entry::new("cargo test", None),
+        ]
+    );
+}
+
+#[test]
+fn multi_line_entries() {
+    let lines = vec![
+        ": 1556993411:0;for f in *; do\\",
+        "  echo $f\\",
+        "done",
+        ": 1556993420:3;cat <<EOF > notes\\",
+        "a b\\",
+        "EOF",
+        ": 1556993430:0;ls \\\\",
+        "  -l",
+    ];
+    let entries: Vec<Entry> = ZshEntries::new(lines.into_iter()).map(Result::unwrap).collect();
+    assert_eq!(
+        entries,
+        vec![
+            timed("for f in *; do\n  echo $f\ndone", 1556993411, 0),
+            timed("cat <<EOF > notes\na b\nEOF", 1556993420, 3),
+            timed("ls \\\n  -l", 1556993430, 0),
+        ]
+    );
+}
+
+#[test]
+fn trailing_continuation() {
+    let lines = vec![": 1556993411:0;echo \\"];
+    let entries: Vec<Entry> = ZshEntries::new(lines.into_iter()).map(Result::unwrap).collect();
+    assert_eq!(entries, vec![timed("echo \\", 1556993411, 0)]);
+}
+
+#[test]
+fn malformed_entries() {
+    let lines = vec![
+        ": 1556993411.:0;cargo fmt",
+        ": 99999999999999999999:0;cargo test\\",
+        "  --release",
+        ": 1556993411:0;cargo build",
+        ": ; echo noop",
+    ];
+    let entries: Vec<_> = ZshEntries::new(lines.into_iter()).collect();
+    assert_eq!(
+        entries,
+        vec![
+            Err(Diagnostic::new(1, Reason::InvalidTimestamp, ": 1556993411.:0;cargo fmt")),
+            Err(Diagnostic::new(
+                2,
+                Reason::InvalidTimestamp,
+                ": 99999999999999999999:0;cargo test\n  --release",
+            )),
+            Ok(timed("cargo build", 1556993411, 0)),
+            Ok(Entry::new(": ; echo noop", None)),
+        ]
+    );
+}
+
+#[test]
+fn wide_and_fractional_timestamps() {
+    let lines = vec![": 946684799:0;ls", ": 4354819200:0;ls", ": 1556993411.25:1;ls"];
+    let times: Vec<_> = ZshEntries::new(lines.into_iter())
+        .map(|entry| entry.unwrap().time.unwrap())
+        .collect();
+    assert_eq!(
+        times,
+        vec![
+            Utc.timestamp(946684799, 0),
+            Utc.timestamp(4354819200, 0),
+            Utc.timestamp(1556993411, 250_000_000),
+        ]
+    );
+}
+
+#[test]
+fn unmetafying() {
+    assert_eq!(unmetafy(b"cargo fmt"), b"cargo fmt".to_vec());
+    // "é" is 0xc3 0xa9, and 0xa9 is metafied as 0x83 0x89
+    assert_eq!(unmetafy(b"caf\xc3\x83\x89"), "caf\u{e9}".as_bytes().to_vec());
+    // Emoji consist of several metafied bytes
+    let metafied = b"\xf0\x83\xbf\x83\xb8\x83\xa0";
+    assert_eq!(unmetafy(metafied), "\u{1f600}".as_bytes().to_vec());
+    assert_eq!(unmetafy(b"dangling\x83"), b"dangling".to_vec());
+}
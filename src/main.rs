@@ -1,23 +1,52 @@
 #[macro_use]
 extern crate prettytable;
 
+use std::process;
+
 use structopt::StructOpt;
 
-use cli::Cli;
+use cli::{Cli, Report};
 
 mod history;
 mod capped_heap;
 #[cfg_attr(tarpaulin, skip)]
 mod cli;
+mod normalize;
+mod shell;
 mod suggest;
+#[cfg(test)]
+mod testing;
 mod trie;
 
+/// Prints the output of a sub-command, separating tables with blank lines
+#[cfg_attr(tarpaulin, skip)]
+fn print(report: Report) {
+    match report {
+        Report::Tables(tables) => {
+            for (i, table) in tables.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                table.printstd();
+            }
+        }
+        Report::Lines(lines) => {
+            for line in lines {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
 #[cfg_attr(tarpaulin, skip)]
 fn main() {
-    let result = match Cli::from_args() {
-        Cli::Suggest(args) => cli::suggest(args).map(|table| table.printstd()),
+    let report = match Cli::from_args() {
+        Cli::Suggest(args) => cli::suggest(args),
+        Cli::Slow(args) => cli::slow(args),
+        Cli::Audit(args) => cli::audit(args),
     };
-    if let Err(e) = result {
+    if let Err(e) = report.map(print) {
         eprintln!("Encountered error: {}", e);
+        process::exit(1);
     }
 }
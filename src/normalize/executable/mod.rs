@@ -0,0 +1,54 @@
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Builtins that run the command given as their arguments, bypassing aliases and functions
+const BYPASSES: &[&str] = &["builtin", "command"];
+
+/// Removes leading invocations like `command git` that run the same command as `git`
+///
+/// `command -v` and `command -V` are kept since they describe the command instead of running it.
+pub fn strip_bypasses(args: &mut Vec<String>) {
+    let mut start = 0;
+    while args.get(start).is_some_and(|arg| BYPASSES.contains(&arg.as_str())) {
+        match skip_options(args, start + 1) {
+            Some(end) if end < args.len() => start = end,
+            _ => break,
+        }
+    }
+    args.drain(..start);
+}
+
+/// Produces the index of the command run by a bypass whose options start at `i`, or `None` if
+/// the bypass only describes the command
+fn skip_options(args: &[String], mut i: usize) -> Option<usize> {
+    while let Some(option) = args.get(i).filter(|arg| arg.starts_with('-')) {
+        match option.as_str() {
+            "-p" => i += 1,
+            "--" => return Some(i + 1),
+            _ => return None,
+        }
+    }
+    Some(i)
+}
+
+/// Produces the name an executable is invoked by, e.g. `git` for `/usr/bin/git`
+///
+/// Only absolute paths are resolved. If `path_dirs` is given, the executable must also be in one
+/// of the directories so that e.g. `/opt/git/bin/git` isn't mistaken for the `git` on `$PATH`.
+pub fn bare_name<'a>(executable: &'a str, path_dirs: Option<&[PathBuf]>) -> &'a str {
+    if !executable.starts_with('/') {
+        return executable;
+    }
+    let path = Path::new(executable);
+    let (dir, name) = match (path.parent(), path.file_name().and_then(|name| name.to_str())) {
+        (Some(dir), Some(name)) => (dir, name),
+        _ => return executable,
+    };
+    match path_dirs {
+        Some(dirs) if !dirs.iter().any(|path_dir| path_dir == dir) => executable,
+        _ => name,
+    }
+}
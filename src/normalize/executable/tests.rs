@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use super::{bare_name, strip_bypasses};
+
+fn stripped(args: &[&str]) -> Vec<String> {
+    let mut args = args.iter().map(|arg| arg.to_string()).collect();
+    strip_bypasses(&mut args);
+    args
+}
+
+#[test]
+fn bypasses() {
+    assert_eq!(stripped(&["git", "status"]), vec!["git", "status"]);
+    assert_eq!(stripped(&["command", "git", "status"]), vec!["git", "status"]);
+    assert_eq!(stripped(&["command", "-p", "git", "status"]), vec!["git", "status"]);
+    assert_eq!(stripped(&["builtin", "cd", "/tmp"]), vec!["cd", "/tmp"]);
+    assert_eq!(stripped(&["command", "--", "builtin", "echo"]), vec!["echo"]);
+}
+
+#[test]
+fn bypasses_without_commands() {
+    assert_eq!(stripped(&["command", "-v", "git"]), vec!["command", "-v", "git"]);
+    assert_eq!(stripped(&["command", "command", "-V", "git"]), vec!["command", "-V", "git"]);
+    assert_eq!(stripped(&["command"]), vec!["command"]);
+    assert_eq!(stripped(&["command", "-p"]), vec!["command", "-p"]);
+}
+
+#[test]
+fn bare_names() {
+    assert_eq!(bare_name("git", None), "git");
+    assert_eq!(bare_name("/usr/bin/git", None), "git");
+    assert_eq!(bare_name("./script.sh", None), "./script.sh");
+    assert_eq!(bare_name("bin/build", None), "bin/build");
+    assert_eq!(bare_name("/", None), "/");
+}
+
+#[test]
+fn bare_names_on_path() {
+    let path = vec![PathBuf::from("/usr/bin"), PathBuf::from("/bin")];
+    assert_eq!(bare_name("/usr/bin/git", Some(&path)), "git");
+    assert_eq!(bare_name("/bin/ls", Some(&path)), "ls");
+    assert_eq!(bare_name("/opt/git/bin/git", Some(&path)), "/opt/git/bin/git");
+    assert_eq!(bare_name("git", Some(&path)), "git");
+}
@@ -0,0 +1,134 @@
+use std::ffi::OsStr;
+use std::path::Path;
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Commands known to parse their options like `getopt`, where `-la` is short for `-l -a`, along
+/// with the letters of their options that take a value, like `curl -X POST` or `curl -XPOST`
+///
+/// Other commands may take long options after a single dash, like `find -name` or `java -jar`.
+const GETOPT_COMMANDS: &[(&str, &str)] = &[
+    ("cat", ""),
+    ("chmod", ""),
+    ("chown", ""),
+    ("cp", "St"),
+    ("curl", "AbcCdDeEFHKmorTuUwxXyYz"),
+    ("df", "Btx"),
+    ("diff", "CDFILSUWxX"),
+    ("du", "BdtX"),
+    ("git", "BCFbcemn"),
+    ("grep", "ABCDdefm"),
+    ("head", "cn"),
+    ("less", "bhjkoOpPtTxyz"),
+    ("ln", "St"),
+    ("ls", "ITw"),
+    ("mkdir", "m"),
+    ("mv", "St"),
+    ("ps", "CGgkOopstUu"),
+    ("rg", "ABCEefgjmMrtT"),
+    ("rm", ""),
+    ("rsync", "BefT"),
+    ("scp", "cFiloPS"),
+    ("sort", "kSotT"),
+    ("ssh", "BbcDEeFIiJLlmOopQRSWw"),
+    ("tail", "cn"),
+    ("tar", "bCfFgHKLNTVX"),
+    ("uniq", "fsw"),
+    ("wc", ""),
+];
+
+/// Canonicalizes the flags of a command so that equivalent spellings have the same arguments
+///
+/// Each run of consecutive flags is sorted, and for commands in `GETOPT_COMMANDS`, bundles of
+/// single-letter flags like `-la` are expanded into `-l -a`. A flag whose value is the next
+/// argument, like `-f` in `tar -xf a.tar`, ends the run so that it stays before its value. The
+/// command itself and any arguments after `--` are left alone.
+pub fn canonicalize<S: AsRef<str>>(args: &[S]) -> Vec<String> {
+    let mut canonical = Vec::with_capacity(args.len());
+    let mut flags = Vec::new();
+    let mut args = args.iter().map(AsRef::as_ref);
+    let command = args.next();
+    let value_letters = command
+        .and_then(|command| Path::new(command).file_name())
+        .and_then(OsStr::to_str)
+        .and_then(|name| GETOPT_COMMANDS.iter().find(|(command, _)| *command == name))
+        .map(|(_, letters)| *letters);
+    canonical.extend(command.map(str::to_string));
+    let mut is_value = false;
+    for arg in args.by_ref() {
+        if is_flag(arg) && !is_value {
+            let takes_value = match value_letters {
+                Some(letters) => expand_bundle(arg, letters, &mut flags),
+                None => {
+                    flags.push(arg.to_string());
+                    false
+                }
+            };
+            if takes_value {
+                let flag = flags.pop();
+                flags.sort();
+                canonical.append(&mut flags);
+                canonical.extend(flag);
+                is_value = true;
+            }
+            continue;
+        }
+        is_value = false;
+        flags.sort();
+        canonical.append(&mut flags);
+        canonical.push(arg.to_string());
+        if arg == "--" {
+            break;
+        }
+    }
+    flags.sort();
+    canonical.append(&mut flags);
+    canonical.extend(args.map(str::to_string));
+    canonical
+}
+
+/// Produces, for each prefix of the canonical arguments, the length of the prefix of `args` it
+/// canonicalizes, if there is one
+///
+/// e.g. for `ls -l -a` the prefix `ls -a -l` is spelled by all three arguments, while `ls -a`
+/// isn't spelled by any prefix.
+pub fn prefix_lengths<S: AsRef<str>>(args: &[S]) -> Vec<Option<usize>> {
+    let canonical = canonicalize(args);
+    let mut lengths = vec![None; canonical.len() + 1];
+    for len in 0..=args.len() {
+        let prefix = canonicalize(&args[..len]);
+        if canonical.starts_with(&prefix) {
+            lengths[prefix.len()] = Some(len);
+        }
+    }
+    lengths
+}
+
+/// Determines whether an argument is a flag, e.g. `-l` or `--hidden`
+fn is_flag(arg: &str) -> bool {
+    arg.len() > 1 && arg.starts_with('-') && arg != "--"
+}
+
+/// Adds the flags of a bundle of single-letter flags like `-la` to `flags`, and determines
+/// whether the last of them takes the next argument as its value
+///
+/// Expansion stops at the first letter in `value_letters`, whose value is the rest of the bundle,
+/// like `POST` in `-XPOST`, or else the next argument.
+fn expand_bundle(flag: &str, value_letters: &str, flags: &mut Vec<String>) -> bool {
+    let letters = &flag[1..];
+    if !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        flags.push(flag.to_string());
+        return false;
+    }
+    for (i, letter) in letters.char_indices() {
+        if value_letters.contains(letter) {
+            let value = &letters[i + 1..];
+            flags.push(format!("-{}{}", letter, value));
+            return value.is_empty();
+        }
+        flags.push(format!("-{}", letter));
+    }
+    false
+}
@@ -0,0 +1,63 @@
+use super::{canonicalize, prefix_lengths};
+
+#[test]
+fn sorted_flags() {
+    assert_eq!(canonicalize(&["rg", "--hidden", "--files"]), vec!["rg", "--files", "--hidden"]);
+    assert_eq!(canonicalize(&["rg", "--files", "--hidden"]), vec!["rg", "--files", "--hidden"]);
+    assert_eq!(
+        canonicalize(&["git", "-C", "repo", "log", "--stat", "--oneline", "-n", "5"]),
+        vec!["git", "-C", "repo", "log", "--oneline", "--stat", "-n", "5"]
+    );
+}
+
+#[test]
+fn expanded_bundles() {
+    assert_eq!(canonicalize(&["ls", "-la"]), vec!["ls", "-a", "-l"]);
+    assert_eq!(canonicalize(&["ls", "-l", "-a"]), vec!["ls", "-a", "-l"]);
+    assert_eq!(canonicalize(&["tail", "-n5"]), vec!["tail", "-n5"]);
+    assert_eq!(canonicalize(&["head", "-20"]), vec!["head", "-20"]);
+    assert_eq!(canonicalize(&["/bin/ls", "-la"]), vec!["/bin/ls", "-a", "-l"]);
+}
+
+#[test]
+fn flag_values() {
+    assert_eq!(canonicalize(&["curl", "-sXPOST", "url"]), vec!["curl", "-XPOST", "-s", "url"]);
+    assert_eq!(
+        canonicalize(&["git", "commit", "-amwip"]),
+        vec!["git", "commit", "-a", "-mwip"]
+    );
+    assert_eq!(
+        canonicalize(&["tar", "-zxvf", "a.tar", "-C", "-dir", "-p"]),
+        vec!["tar", "-v", "-x", "-z", "-f", "a.tar", "-C", "-dir", "-p"]
+    );
+    assert_eq!(
+        canonicalize(&["ls", "-l", "-I", "*.o", "-a"]),
+        vec!["ls", "-l", "-I", "*.o", "-a"]
+    );
+    assert_eq!(canonicalize(&["ls", "-Ia"]), vec!["ls", "-Ia"]);
+}
+
+#[test]
+fn single_dash_long_options() {
+    assert_eq!(canonicalize(&["find", "-type", "-name"]), vec!["find", "-name", "-type"]);
+    assert_eq!(canonicalize(&["java", "-jar", "app.jar"]), vec!["java", "-jar", "app.jar"]);
+    assert_eq!(canonicalize(&["gcc", "-Wall", "-O2"]), vec!["gcc", "-O2", "-Wall"]);
+}
+
+#[test]
+fn untouched_arguments() {
+    assert_eq!(canonicalize(&["-b", "-a"]), vec!["-b", "-a"]);
+    assert_eq!(canonicalize(&["rm", "--", "-b", "-a"]), vec!["rm", "--", "-b", "-a"]);
+    assert_eq!(canonicalize(&["cat", "-"]), vec!["cat", "-"]);
+    assert_eq!(canonicalize::<&str>(&[]), Vec::<String>::new());
+}
+
+#[test]
+fn prefixes() {
+    assert_eq!(prefix_lengths(&["ls", "-l", "-a"]), vec![Some(0), Some(1), None, Some(3)]);
+    assert_eq!(prefix_lengths(&["ls", "-la"]), vec![Some(0), Some(1), None, Some(2)]);
+    assert_eq!(
+        prefix_lengths(&["rg", "--files", "--hidden"]),
+        vec![Some(0), Some(1), Some(2), Some(3)]
+    );
+}
@@ -0,0 +1,140 @@
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+
+use crate::history::ExecutedCommand;
+
+mod executable;
+mod flags;
+mod wrapper;
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// A command prepared to be counted, with the wrappers it was run under split off
+#[derive(Debug, PartialEq)]
+pub struct NormalizedCommand {
+    /// Arguments of the command that was actually run
+    pub args: Vec<String>,
+    /// Wrapper commands and environment assignments the command was run under, e.g. `sudo`
+    pub wrapper: Vec<String>,
+    /// Time of execution
+    pub time: Option<DateTime<Utc>>,
+    /// Number of seconds the command took to run
+    pub duration: Option<u32>,
+    /// Arguments the shell recorded as existing paths, if they were kept
+    pub paths: Vec<String>,
+    /// The arguments as they were spelled before their flags were canonicalized, if they were
+    pub spelling: Option<Vec<String>>,
+    /// Length of the prefix of `spelling` that spells each prefix of `args`, if any
+    spelling_lengths: Vec<Option<usize>>,
+}
+
+impl NormalizedCommand {
+    /// Produces how the first `depth` arguments were spelled before their flags were
+    /// canonicalized, if they were and some prefix of the spelling canonicalizes to them
+    pub fn spelling(&self, depth: usize) -> Option<&[String]> {
+        let len = (*self.spelling_lengths.get(depth)?)?;
+        let spelling = self.spelling.as_ref()?;
+        Some(&spelling[..len]).filter(|spelling| !spelling.is_empty())
+    }
+}
+
+/// Normalizes executed commands so that equivalent commands are counted together
+#[derive(Clone, Debug, Default)]
+pub struct Normalizer {
+    /// Whether wrapper commands like `sudo` are kept as part of the commands they run
+    pub keep_wrappers: bool,
+    /// Whether invocations like `/usr/bin/git` and `command git` are kept as they were written
+    pub keep_paths: bool,
+    /// Directories executables must be in for their paths to be removed, e.g. those in `$PATH`
+    pub path_dirs: Option<Vec<PathBuf>>,
+    /// Whether flags are canonicalized, e.g. so that `ls -la` and `ls -a -l` are counted together
+    pub canonical_flags: bool,
+    /// Commands that aliases expand to by name, which are expanded before commands are counted
+    pub aliases: HashMap<String, Vec<String>>,
+}
+
+impl Normalizer {
+    /// Normalizes an executed command
+    ///
+    /// Aliases the command starts with are expanded first, each at most once. Unless
+    /// `keep_wrappers` is set, commands like `sudo` and `nice -n 10` and environment assignments
+    /// like `FOO=1` are split off into the command's `wrapper`. Unless `keep_paths` is set,
+    /// commands run by path or through `command` are counted by their bare name. If
+    /// `canonical_flags` is set, runs of flags are sorted and bundled flags of well-known commands
+    /// are expanded.
+    pub fn normalize(&self, command: ExecutedCommand) -> NormalizedCommand {
+        self.normalize_command(command, true)
+    }
+
+    /// Normalizes the command an alias or abbreviation expands to, without expanding aliases
+    pub fn normalize_expansion(&self, args: Vec<String>) -> Vec<String> {
+        let command = ExecutedCommand { args, time: None, duration: None, paths: Vec::new() };
+        self.normalize_command(command, false).args
+    }
+
+    /// Normalizes an executed command, expanding aliases if `expand` is set
+    fn normalize_command(&self, command: ExecutedCommand, expand: bool) -> NormalizedCommand {
+        let ExecutedCommand { mut args, time, duration, paths } = command;
+        if expand {
+            self.expand_aliases(&mut args);
+        }
+        let mut wrapper = Vec::new();
+        loop {
+            self.resolve_executable(&mut args);
+            let wrapped = if self.keep_wrappers { 0 } else { wrapper::prefix_len(&args) };
+            if wrapped == 0 {
+                break;
+            }
+            wrapper.extend(args.drain(..wrapped));
+        }
+        let (spelling, spelling_lengths) = if self.canonical_flags {
+            let spelling_lengths = flags::prefix_lengths(&args);
+            let canonical = flags::canonicalize(&args);
+            (Some(mem::replace(&mut args, canonical)), spelling_lengths)
+        } else {
+            (None, Vec::new())
+        };
+        NormalizedCommand {
+            args,
+            wrapper,
+            time,
+            duration,
+            paths,
+            spelling,
+            spelling_lengths,
+        }
+    }
+
+    /// Expands the alias a command starts with, and then any alias its expansion starts with
+    ///
+    /// Like in a shell, an alias isn't expanded again within its own expansion, so that aliases
+    /// like `ls='ls -G'` are expanded once.
+    fn expand_aliases(&self, args: &mut Vec<String>) {
+        let mut expanded = HashSet::new();
+        while let Some(name) = args.first().cloned() {
+            let expansion = match self.aliases.get(&name) {
+                Some(expansion) if expanded.insert(name) => expansion,
+                _ => break,
+            };
+            args.splice(..1, expansion.iter().cloned());
+        }
+    }
+
+    /// Replaces invocations like `/usr/bin/git` or `command git` with the bare command name
+    fn resolve_executable(&self, args: &mut Vec<String>) {
+        if self.keep_paths {
+            return;
+        }
+        executable::strip_bypasses(args);
+        if let Some(executable) = args.first_mut() {
+            let name = executable::bare_name(executable, self.path_dirs.as_deref());
+            if name.len() < executable.len() {
+                *executable = name.to_string();
+            }
+        }
+    }
+}
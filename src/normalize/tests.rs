@@ -0,0 +1,114 @@
+use std::iter::FromIterator;
+use std::path::PathBuf;
+
+use crate::history::{ExecutedCommand, History};
+use crate::testing::strings;
+
+use super::{NormalizedCommand, Normalizer};
+
+fn command(args: &[&str]) -> ExecutedCommand {
+    ExecutedCommand {
+        args: strings(args),
+        time: None,
+        duration: Some(3),
+        paths: Vec::new(),
+    }
+}
+
+#[test]
+fn strip_wrappers() {
+    let normalizer = Normalizer::default();
+    assert_eq!(
+        normalizer.normalize(command(&["sudo", "-u", "www", "FOO=1", "systemctl", "restart"])),
+        NormalizedCommand {
+            args: strings(&["systemctl", "restart"]),
+            wrapper: strings(&["sudo", "-u", "www", "FOO=1"]),
+            time: None,
+            duration: Some(3),
+            paths: Vec::new(),
+            spelling: None,
+            spelling_lengths: Vec::new(),
+        }
+    );
+    assert_eq!(normalizer.normalize(command(&["sudo", "-i"])).args, vec!["sudo", "-i"]);
+}
+
+#[test]
+fn keep_wrappers() {
+    let normalizer = Normalizer {
+        keep_wrappers: true,
+        ..Normalizer::default()
+    };
+    let normalized = normalizer.normalize(command(&["sudo", "systemctl", "restart"]));
+    assert_eq!(normalized.args, vec!["sudo", "systemctl", "restart"]);
+    assert!(normalized.wrapper.is_empty());
+}
+
+#[test]
+fn resolve_executables() {
+    let hist = History::from_iter(&[
+        "/usr/bin/git status",
+        "command git status",
+        "\\git status",
+        "git status",
+        "sudo /usr/bin/env FOO=1 command -p /usr/bin/git status",
+    ]);
+    let normalizer = Normalizer::default();
+    for command in hist.commands {
+        assert_eq!(normalizer.normalize(command).args, vec!["git", "status"]);
+    }
+}
+
+#[test]
+fn keep_paths() {
+    let normalizer = Normalizer {
+        keep_paths: true,
+        ..Normalizer::default()
+    };
+    let normalized = normalizer.normalize(command(&["command", "/usr/bin/git", "status"]));
+    assert_eq!(normalized.args, vec!["command", "/usr/bin/git", "status"]);
+}
+
+#[test]
+fn check_path_dirs() {
+    let normalizer = Normalizer {
+        path_dirs: Some(vec![PathBuf::from("/usr/bin")]),
+        ..Normalizer::default()
+    };
+    let normalized = normalizer.normalize(command(&["/usr/bin/git", "status"]));
+    assert_eq!(normalized.args, vec!["git", "status"]);
+    let normalized = normalizer.normalize(command(&["/opt/git/bin/git", "status"]));
+    assert_eq!(normalized.args, vec!["/opt/git/bin/git", "status"]);
+}
+
+#[test]
+fn canonical_flags() {
+    let normalizer = Normalizer {
+        canonical_flags: true,
+        ..Normalizer::default()
+    };
+    let normalized = normalizer.normalize(command(&["sudo", "ls", "-la", "src"]));
+    assert_eq!(normalized.args, vec!["ls", "-a", "-l", "src"]);
+    assert_eq!(normalized.wrapper, vec!["sudo"]);
+    assert_eq!(normalized.spelling, Some(strings(&["ls", "-la", "src"])));
+    assert_eq!(normalized.spelling(0), None);
+    assert_eq!(normalized.spelling(1), Some(&strings(&["ls"])[..]));
+    assert_eq!(normalized.spelling(2), None);
+    assert_eq!(normalized.spelling(4), Some(&strings(&["ls", "-la", "src"])[..]));
+
+    let normalized = Normalizer::default().normalize(command(&["ls", "-la"]));
+    assert_eq!(normalized.args, vec!["ls", "-la"]);
+    assert_eq!(normalized.spelling(2), None);
+}
+
+#[test]
+fn expand_aliases() {
+    let mut normalizer = Normalizer::default();
+    normalizer.aliases.insert("ls".to_string(), strings(&["ls", "-G"]));
+    normalizer.aliases.insert("g".to_string(), strings(&["git"]));
+    normalizer.aliases.insert("gs".to_string(), strings(&["g", "status"]));
+    let normalized = normalizer.normalize(command(&["ls", "src"]));
+    assert_eq!(normalized.args, vec!["ls", "-G", "src"]);
+    assert_eq!(normalizer.normalize(command(&["gs", "-s"])).args, vec!["git", "status", "-s"]);
+    assert_eq!(normalizer.normalize_expansion(strings(&["g", "status"])), vec!["g", "status"]);
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// A command that runs the command given as its arguments
+struct Wrapper {
+    name: &'static str,
+    /// Options of the wrapper that take a value as the following argument
+    options_with_values: &'static [&'static str],
+}
+
+const WRAPPERS: &[Wrapper] = &[
+    Wrapper {
+        name: "sudo",
+        options_with_values: &[
+            "-C", "-D", "-R", "-T", "-U", "-g", "-p", "-r", "-t", "-u", "--chdir", "--chroot",
+            "--close-from", "--command-timeout", "--group", "--other-user", "--prompt", "--role",
+            "--type", "--user",
+        ],
+    },
+    Wrapper {
+        name: "doas",
+        options_with_values: &["-C", "-u"],
+    },
+    Wrapper {
+        name: "env",
+        options_with_values: &["-C", "-S", "-u", "--chdir", "--split-string", "--unset"],
+    },
+    Wrapper {
+        name: "time",
+        options_with_values: &["-f", "-o", "--format", "--output"],
+    },
+    Wrapper {
+        name: "nohup",
+        options_with_values: &[],
+    },
+    Wrapper {
+        name: "nice",
+        options_with_values: &["-n", "--adjustment"],
+    },
+];
+
+/// Determines whether an argument assigns an environment variable, e.g. `RUST_LOG=debug`
+pub fn is_assignment(arg: &str) -> bool {
+    match arg.find('=') {
+        Some(0) | None => false,
+        Some(i) => {
+            let name = &arg[..i];
+            !name.starts_with(|c: char| c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+    }
+}
+
+/// Produces the number of leading arguments that wrap the command that was actually run
+///
+/// Wrappers like `sudo -u root` and environment assignments like `FOO=1` are skipped, however
+/// they are nested, as long as there is a command left after them.
+pub fn prefix_len<S: AsRef<str>>(args: &[S]) -> usize {
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_ref();
+        if is_assignment(arg) {
+            i += 1;
+        } else if let Some(wrapper) = WRAPPERS.iter().find(|wrapper| wrapper.name == arg) {
+            i = skip_options(args, i + 1, wrapper);
+        } else {
+            return i;
+        }
+    }
+    // Nothing is left to be the wrapped command
+    0
+}
+
+/// Produces the index of the first argument after the options of a wrapper starting at `i`
+fn skip_options<S: AsRef<str>>(args: &[S], mut i: usize, wrapper: &Wrapper) -> usize {
+    while let Some(arg) = args.get(i).map(AsRef::as_ref) {
+        if arg == "--" {
+            return i + 1;
+        } else if !arg.starts_with('-') || arg == "-" {
+            break;
+        }
+        i += if wrapper.options_with_values.contains(&arg) { 2 } else { 1 };
+    }
+    i
+}
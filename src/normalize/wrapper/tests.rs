@@ -0,0 +1,42 @@
+use super::{is_assignment, prefix_len};
+
+#[test]
+fn assignments() {
+    assert!(is_assignment("FOO=1"));
+    assert!(is_assignment("RUST_LOG="));
+    assert!(!is_assignment("=1"));
+    assert!(!is_assignment("1FOO=1"));
+    assert!(!is_assignment("--features=serde"));
+    assert!(!is_assignment("cargo"));
+}
+
+#[test]
+fn wrappers() {
+    assert_eq!(prefix_len(&["systemctl", "restart", "nginx"]), 0);
+    assert_eq!(prefix_len(&["sudo", "systemctl", "restart", "nginx"]), 1);
+    assert_eq!(prefix_len(&["sudo", "-u", "postgres", "psql"]), 3);
+    assert_eq!(prefix_len(&["sudo", "-upostgres", "-E", "psql"]), 3);
+    assert_eq!(prefix_len(&["time", "-p", "make"]), 2);
+    assert_eq!(prefix_len(&["nohup", "./server", "-p", "80"]), 1);
+    assert_eq!(prefix_len(&["nice", "-n", "10", "make"]), 3);
+    assert_eq!(prefix_len(&["nice", "-10", "make"]), 2);
+    assert_eq!(prefix_len(&["env", "--", "-weird"]), 2);
+}
+
+#[test]
+fn assignments_and_nested_wrappers() {
+    assert_eq!(prefix_len(&["FOO=1", "cargo", "test"]), 1);
+    assert_eq!(prefix_len(&["FOO=1", "BAR=2", "cargo", "test"]), 2);
+    assert_eq!(prefix_len(&["env", "-u", "HOME", "FOO=1", "cargo", "test"]), 4);
+    assert_eq!(prefix_len(&["sudo", "nice", "-n", "5", "time", "make"]), 5);
+    assert_eq!(prefix_len(&["cargo", "test", "FOO=1"]), 0);
+}
+
+#[test]
+fn wrappers_without_commands() {
+    assert_eq!(prefix_len(&["sudo"]), 0);
+    assert_eq!(prefix_len(&["sudo", "-i"]), 0);
+    assert_eq!(prefix_len(&["FOO=1"]), 0);
+    assert_eq!(prefix_len(&["time"]), 0);
+    assert_eq!(prefix_len::<&str>(&[]), 0);
+}
@@ -0,0 +1,78 @@
+use std::{fmt, str::FromStr};
+
+pub mod names;
+pub mod rc;
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Shells that suggestions can be defined for
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    /// Names of the shells, as accepted by `from_str`
+    pub const VARIANTS: &'static [&'static str] = &["bash", "zsh", "fish"];
+
+    /// Quotes a string so that the shell reads it back as a single, identical word
+    ///
+    /// Strings are always quoted, even if they needn't be, so that definitions read alike.
+    pub fn quote(self, s: &str) -> String {
+        match self {
+            Shell::Fish => format!("'{}'", s.replace('\\', r"\\").replace('\'', r"\'")),
+            Shell::Bash | Shell::Zsh => format!("'{}'", s.replace('\'', r"'\''")),
+        }
+    }
+
+    /// Produces the definition of an alias, which is an abbreviation in fish
+    pub fn alias(self, name: &str, command: &str) -> String {
+        match self {
+            Shell::Fish => format!("abbr -a {} {}", name, self.quote(command)),
+            Shell::Bash | Shell::Zsh => format!("alias {}={}", name, self.quote(command)),
+        }
+    }
+
+    /// Produces a reference to the `n`th argument of a function, counting from 1
+    pub fn argument(self, n: usize) -> String {
+        match self {
+            Shell::Fish => format!("$argv[{}]", n),
+            Shell::Bash | Shell::Zsh => format!("\"${}\"", n),
+        }
+    }
+
+    /// Produces the definition of a function running `body`
+    pub fn function(self, name: &str, body: &str) -> String {
+        match self {
+            Shell::Fish => format!("function {}; {}; end", name, body),
+            Shell::Bash | Shell::Zsh => format!("{}() {{ {}; }}", name, body),
+        }
+    }
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            _ => Err(format!("Unknown shell: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        };
+        write!(f, "{}", name)
+    }
+}
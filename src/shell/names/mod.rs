@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Builtins and reserved words of bash, zsh and fish, which names must never shadow
+const BUILTINS: &[&str] = &[
+    "abbr", "alias", "and", "autoload", "begin", "bg", "bind", "bindkey", "break", "builtin",
+    "case", "cd", "command", "compgen", "complete", "contains", "continue", "count", "declare",
+    "dirs", "disown", "do", "done", "echo", "elif", "else", "emulate", "enable", "end", "esac",
+    "eval", "exec", "exit", "export", "false", "fc", "fg", "fi", "for", "function", "functions",
+    "getopts", "hash", "help", "history", "if", "in", "jobs", "kill", "let", "local", "logout",
+    "math", "noglob", "not", "or", "popd", "print", "printf", "pushd", "pwd", "read", "readonly",
+    "return", "select", "set", "setopt", "shift", "shopt", "source", "status", "string",
+    "suspend", "test", "then", "time", "times", "trap", "true", "type", "typeset", "ulimit",
+    "umask", "unalias", "unset", "unsetopt", "until", "wait", "whence", "where", "which",
+    "while", "zle", "zmodload",
+];
+
+/// Conventional names for common commands, which are extended with the initials of any further
+/// arguments, e.g. `gsts` for `git status -s`
+const CONVENTIONS: &[(&[&str], &str)] = &[
+    (&["git"], "g"),
+    (&["git", "add"], "ga"),
+    (&["git", "branch"], "gb"),
+    (&["git", "checkout"], "gco"),
+    (&["git", "commit"], "gc"),
+    (&["git", "diff"], "gd"),
+    (&["git", "fetch"], "gf"),
+    (&["git", "pull"], "gl"),
+    (&["git", "push"], "gp"),
+    (&["git", "rebase"], "grb"),
+    (&["git", "remote"], "gr"),
+    (&["git", "status"], "gst"),
+    (&["kubectl"], "k"),
+    (&["kubectl", "get", "pods"], "kgp"),
+    (&["docker-compose"], "dco"),
+    (&["docker", "compose"], "dco"),
+];
+
+/// Produces the initials of arguments, e.g. `rfh` for `rg --files --hidden`
+fn initials<S: AsRef<str>>(args: &[S]) -> String {
+    args.iter()
+        .filter_map(|arg| arg.as_ref().chars().find(|c| c.is_alphanumeric()))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Produces candidate names for an alias of a command, from the most to the least mnemonic
+///
+/// Conventional names come first, then the initials of the arguments, then the initials with
+/// more letters of the last argument, e.g. `rfhi` for `rg --files --hidden`.
+pub fn candidates<S: AsRef<str>>(args: &[S]) -> Vec<String> {
+    let mut candidates = Vec::new();
+    let convention = CONVENTIONS
+        .iter()
+        .filter(|(prefix, _)| {
+            prefix.len() <= args.len() && prefix.iter().zip(args).all(|(a, b)| *a == b.as_ref())
+        })
+        .max_by_key(|(prefix, _)| prefix.len());
+    if let Some((prefix, name)) = convention {
+        candidates.push(format!("{}{}", name, initials(&args[prefix.len()..])));
+    }
+    candidates.push(initials(args));
+    if let Some((last, rest)) = args.split_last() {
+        let letters: Vec<char> = last.as_ref().chars().filter(|c| c.is_alphanumeric()).collect();
+        for len in 2..=letters.len().min(3) {
+            let extra: String = letters[..len].iter().flat_map(|c| c.to_lowercase()).collect();
+            candidates.push(format!("{}{}", initials(rest), extra));
+        }
+    }
+    // Aliasing the command to its own name, like `gs` for an unexpanded alias `gs`, is no use
+    let command = args.first().map(|command| command.as_ref());
+    let mut seen = HashSet::new();
+    candidates.retain(|candidate| {
+        let is_command = Some(candidate.as_str()) == command;
+        !candidate.is_empty() && !is_command && seen.insert(candidate.to_owned())
+    });
+    candidates
+}
+
+/// Produces the names of the files in directories, such as the executables on `$PATH`
+///
+/// Directories that can't be read are skipped.
+pub fn executables(dirs: &[PathBuf]) -> impl Iterator<Item=String> + '_ {
+    dirs.iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+}
+
+/// Chooses names that don't shadow executables, builtins, existing aliases or each other
+#[derive(Debug)]
+pub struct Namer {
+    taken: HashSet<String>,
+}
+
+impl Namer {
+    /// Most numbered variants of a candidate tried when every candidate is taken
+    const MAX_NUMBER: usize = 9;
+
+    /// Initializes a `Namer` that avoids shell builtins and reserved words
+    pub fn new() -> Self {
+        Namer {
+            taken: BUILTINS.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    /// Avoids names such as those of executables or existing aliases
+    pub fn reserve<I>(&mut self, names: I)
+        where
+            I: IntoIterator<Item=String>,
+    {
+        self.taken.extend(names);
+    }
+
+    /// Chooses the first candidate that is a valid name and isn't taken, or else a numbered
+    /// variant of the first candidate like `rfh2`, and reserves it
+    pub fn choose<I>(&mut self, candidates: I) -> Option<String>
+        where
+            I: IntoIterator<Item=String>,
+    {
+        let name = self.peek(candidates)?;
+        self.taken.insert(name.to_owned());
+        Some(name)
+    }
+
+    /// Produces the name `choose` would choose, without reserving it
+    pub fn peek<I>(&self, candidates: I) -> Option<String>
+        where
+            I: IntoIterator<Item=String>,
+    {
+        let candidates: Vec<String> = candidates
+            .into_iter()
+            .filter(|name| name.starts_with(|c: char| c.is_alphabetic()))
+            .collect();
+        let numbered = candidates.first().into_iter().flat_map(|first| {
+            (2..=Self::MAX_NUMBER).map(move |number| format!("{}{}", first, number))
+        });
+        candidates.iter().cloned().chain(numbered).find(|name| !self.taken.contains(name))
+    }
+}
+
+impl Default for Namer {
+    fn default() -> Self {
+        Namer::new()
+    }
+}
@@ -0,0 +1,52 @@
+use std::fs::{self, File};
+
+use super::{candidates, executables, Namer};
+
+#[test]
+fn initials() {
+    assert_eq!(candidates(&["rg", "--files", "--hidden"]), vec!["rfh", "rfhi", "rfhid"]);
+    assert_eq!(candidates(&["ls"]), vec!["l"]);
+    assert_eq!(candidates(&["gs"]), vec!["g"]);
+    assert_eq!(candidates(&["./run.sh", "-v"]), vec!["rv"]);
+    assert!(candidates(&["..", "-"]).is_empty());
+}
+
+#[test]
+fn conventions() {
+    assert_eq!(candidates(&["git", "status"])[0], "gst");
+    assert_eq!(candidates(&["git", "status", "-s"])[0], "gsts");
+    assert_eq!(candidates(&["git", "log"]), vec!["gl", "glo", "glog"]);
+    assert_eq!(candidates(&["kubectl", "get", "pods"])[0], "kgp");
+    assert_eq!(candidates(&["kubectl", "get", "svc"])[0], "kgs");
+}
+
+#[test]
+fn collisions() {
+    let mut namer = Namer::new();
+    namer.reserve(vec!["gst".to_string()]);
+    assert_eq!(namer.peek(candidates(&["git", "status"])), Some("gs".to_string()));
+    assert_eq!(namer.choose(candidates(&["git", "status"])), Some("gs".to_string()));
+    assert_eq!(namer.choose(candidates(&["git", "stash"])), Some("gsta".to_string()));
+    assert_eq!(namer.choose(vec!["cd".to_string()]), Some("cd2".to_string()));
+    assert_eq!(namer.choose(vec!["1up".to_string()]), None);
+    assert_eq!(namer.choose(Vec::new()), None);
+}
+
+#[test]
+fn exhausted() {
+    let mut namer = Namer::new();
+    let names: Vec<_> = (0..10).map(|_| namer.choose(vec!["x".to_string()])).collect();
+    assert_eq!(names[0], Some("x".to_string()));
+    assert_eq!(names[8], Some("x9".to_string()));
+    assert_eq!(names[9], None);
+}
+
+#[test]
+fn path_executables() {
+    let dir = std::env::temp_dir().join(format!("rags-names-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    File::create(dir.join("rfh")).unwrap();
+    let names: Vec<String> = executables(&[dir.clone(), dir.join("missing")]).collect();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(names, vec!["rfh"]);
+}
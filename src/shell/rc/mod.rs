@@ -0,0 +1,240 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::history::{Environment, is_incomplete, split_commands, unquote};
+use crate::shell::Shell;
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Matches the start of a function definition, like `gs() {`, `function gs {` or fish's
+/// `function gs`
+const FUNCTION_PATTERN: &str =
+    r"^\s*(function\s+(?P<keyword>[\w.:-]+)|(?P<posix>[A-Za-z_][\w.:-]*)\s*\(\s*\))";
+
+/// Options of fish's `abbr` that take a value
+const ABBR_VALUE_OPTIONS: &[&str] = &["--position", "-p", "--regex", "--set-cursor"];
+/// Options of fish's `abbr` that don't define an abbreviation
+const ABBR_QUERY_OPTIONS: &[&str] =
+    &["-e", "--erase", "-l", "--list", "-s", "--show", "-q", "--query", "-r", "--rename"];
+
+/// Represents the definitions of shell startup files, like `~/.bashrc`
+#[derive(Debug, Default, PartialEq)]
+pub struct StartupFiles {
+    /// Names and values of the aliases defined
+    pub aliases: Vec<(String, String)>,
+    /// Names and values of zsh's global and suffix aliases, which don't name a command
+    pub global_aliases: Vec<(String, String)>,
+    /// Names and expansions of fish's abbreviations defined
+    pub abbreviations: Vec<(String, String)>,
+    /// Names of the functions defined
+    pub functions: Vec<String>,
+    /// Sourced files that couldn't be read, and why
+    pub unreadable: Vec<(PathBuf, String)>,
+    /// Files read so far, which aren't read again
+    read: HashSet<PathBuf>,
+}
+
+impl StartupFiles {
+    /// Reads the definitions of a startup file of bash, zsh or fish, along with those of the files
+    /// it sources
+    ///
+    /// Sourced files that don't exist are skipped, since they are often only sourced if they do,
+    /// and those that can't be read are skipped and recorded in `unreadable`. A leading `~` or
+    /// `$HOME` in their paths is replaced with `home`.
+    pub fn read(&mut self, path: &Path, home: Option<&Path>) -> io::Result<()> {
+        if !self.read.insert(path.to_owned()) {
+            return Ok(());
+        }
+        let contents = fs::read(path)?;
+        let contents = String::from_utf8_lossy(&contents);
+        let function_re = Regex::new(FUNCTION_PATTERN).unwrap();
+        for command in complete_commands(contents.lines()) {
+            self.add(&command, &function_re);
+            for sourced in sourced_files(&command, path.parent(), home) {
+                if sourced.is_file() {
+                    if let Err(error) = self.read(&sourced, home) {
+                        self.unreadable.push((sourced, error.to_string()));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Produces the names defined, whether as aliases, abbreviations or functions
+    pub fn names(&self) -> impl Iterator<Item=&String> {
+        let aliases = self.current_aliases().chain(&self.global_aliases);
+        let aliases = aliases.chain(&self.abbreviations).map(|(name, _)| name);
+        aliases.chain(&self.functions)
+    }
+
+    /// Produces the aliases in effect, skipping definitions replaced by later ones
+    pub fn current_aliases(&self) -> impl Iterator<Item=&(String, String)> {
+        let last: HashMap<&String, usize> =
+            self.aliases.iter().enumerate().map(|(i, (name, _))| (name, i)).collect();
+        let aliases = self.aliases.iter().enumerate();
+        aliases.filter(move |(i, (name, _))| last[name] == *i).map(|(_, alias)| alias)
+    }
+
+    /// Produces the commands aliases expand to by name, skipping those that run several commands
+    pub fn alias_expansions(&self) -> impl Iterator<Item=(&String, Vec<String>)> {
+        let aliases = self.current_aliases();
+        aliases.filter_map(|(name, value)| Some((name, single_command(value)?)))
+    }
+
+    /// Produces the commands aliases and abbreviations expand to, which needn't be suggested
+    pub fn expansions(&self) -> impl Iterator<Item=Vec<String>> + '_ {
+        let definitions = self.current_aliases().chain(&self.abbreviations);
+        definitions.filter_map(|(_, value)| single_command(value))
+    }
+
+    /// Adds the definitions of a complete command line
+    fn add(&mut self, line: &str, function_re: &Regex) {
+        if let Some(captures) = function_re.captures(line) {
+            let name = captures.name("keyword").or_else(|| captures.name("posix")).unwrap();
+            self.functions.push(name.as_str().to_owned());
+        }
+        for words in split_commands(line) {
+            let words: Vec<String> = words.iter().map(|word| unquote(word).into_owned()).collect();
+            match words.first().map(String::as_str) {
+                Some("alias") if is_global(&words[1..]) => {
+                    self.global_aliases.extend(aliases(&words[1..]))
+                }
+                Some("alias") => self.aliases.extend(aliases(&words[1..])),
+                Some("abbr") => self.abbreviations.extend(abbreviation(&words[1..])),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Joins lines into complete command lines, like a shell reading them
+fn complete_commands<I, T>(lines: I) -> Vec<String>
+    where
+        I: IntoIterator<Item=T>,
+        T: AsRef<str>,
+{
+    let mut commands = Vec::new();
+    let mut pending = String::new();
+    for line in lines {
+        if !pending.is_empty() {
+            pending.push('\n');
+        }
+        pending.push_str(line.as_ref());
+        if !is_incomplete(&pending) {
+            commands.push(pending.split_off(0));
+        }
+    }
+    if !pending.is_empty() {
+        commands.push(pending);
+    }
+    commands
+}
+
+/// Splits a command line into words if it runs a single command
+fn single_command(line: &str) -> Option<Vec<String>> {
+    let mut commands = split_commands(line);
+    if commands.len() == 1 { commands.pop() } else { None }
+}
+
+/// Parses the arguments of an `alias` command
+///
+/// Bash and zsh define any number of aliases like `name=value`, while fish defines a single one
+/// like `name value`. Options like zsh's `-g` are skipped.
+fn aliases(args: &[String]) -> Vec<(String, String)> {
+    let args: Vec<&String> = args.iter().skip_while(|arg| arg.starts_with('-')).collect();
+    match args.split_first() {
+        Some((name, value)) if !name.contains('=') && !value.is_empty() => {
+            let value: Vec<&str> = value.iter().map(|arg| arg.as_str()).collect();
+            vec![(name.to_string(), value.join(" "))]
+        }
+        _ => args
+            .iter()
+            .filter_map(|arg| {
+                let (name, value) = arg.split_once('=')?;
+                Some((name.to_owned(), value.to_owned()))
+            })
+            .collect(),
+    }
+}
+
+/// Determines whether the arguments of an `alias` command define zsh global aliases like
+/// `alias -g G='| grep'` or suffix aliases like `alias -s txt=less`
+fn is_global(args: &[String]) -> bool {
+    args.iter()
+        .take_while(|arg| arg.starts_with('-'))
+        .any(|option| option.contains(['g', 's']))
+}
+
+/// Parses the arguments of fish's `abbr` command, if it defines an abbreviation
+fn abbreviation(args: &[String]) -> Option<(String, String)> {
+    let mut words = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if ABBR_QUERY_OPTIONS.contains(&arg.as_str()) {
+            return None;
+        } else if ABBR_VALUE_OPTIONS.contains(&arg.as_str()) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            words.push(arg.as_str());
+        }
+    }
+    let (name, expansion) = words.split_first()?;
+    Some((name.to_string(), expansion.join(" "))).filter(|_| !expansion.is_empty())
+}
+
+/// Produces the paths of the files a command line sources, relative to `dir`
+///
+/// Paths with expansions other than a leading `~` or `$HOME` can't be resolved and are skipped.
+fn sourced_files(line: &str, dir: Option<&Path>, home: Option<&Path>) -> Vec<PathBuf> {
+    split_commands(line)
+        .into_iter()
+        .filter(|words| words.len() >= 2 && (words[0] == "source" || words[0] == "."))
+        .filter_map(|words| {
+            let path = unquote(&words[1]).into_owned();
+            let home_relative = ["~/", "$HOME/", "${HOME}/"]
+                .iter()
+                .find_map(|prefix| path.strip_prefix(prefix));
+            match home_relative {
+                Some(relative) => Some(home?.join(relative)),
+                None if path.contains('$') || path.starts_with('~') => None,
+                None => Some(dir.map_or_else(|| PathBuf::from(&path), |dir| dir.join(&path))),
+            }
+        })
+        .collect()
+}
+
+/// Finds the startup files of the user's login shell, or of every shell if it isn't known
+pub fn locate(env: &Environment) -> Vec<PathBuf> {
+    let shell = env
+        .shell
+        .as_ref()
+        .and_then(|shell| shell.file_name())
+        .and_then(OsStr::to_str)
+        .and_then(|name| name.parse::<Shell>().ok());
+    let shells = match shell {
+        Some(shell) => vec![shell],
+        None => vec![Shell::Zsh, Shell::Bash, Shell::Fish],
+    };
+    let home = env.home.as_ref();
+    shells
+        .into_iter()
+        .filter_map(|shell| match shell {
+            Shell::Zsh => env.zdotdir.as_ref().or(home).map(|dir| dir.join(".zshrc")),
+            Shell::Bash => home.map(|home| home.join(".bashrc")),
+            Shell::Fish => env
+                .config_home
+                .clone()
+                .or_else(|| home.map(|home| home.join(".config")))
+                .map(|config| config.join("fish").join("config.fish")),
+        })
+        .filter(|path| path.is_file())
+        .collect()
+}
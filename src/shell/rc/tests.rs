@@ -0,0 +1,152 @@
+use std::fs::{self, create_dir_all};
+use std::path::PathBuf;
+
+use tempfile::{tempdir, TempDir};
+
+use crate::history::Environment;
+use crate::testing::strings;
+
+use super::{locate, StartupFiles};
+
+fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+}
+
+fn home_with(files: &[(&str, &str)]) -> TempDir {
+    let home = tempdir().unwrap();
+    for (file, contents) in files {
+        let path = home.path().join(file);
+        create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    home
+}
+
+fn read(home: &TempDir, file: &str) -> StartupFiles {
+    let mut rc = StartupFiles::default();
+    rc.read(&home.path().join(file), Some(home.path())).unwrap();
+    rc
+}
+
+#[test]
+fn posix_aliases() {
+    let home = home_with(&[(
+        ".bashrc",
+        "# aliases\n\
+         alias ll='ls -la'\n\
+         alias gs=\"git status\" gd='git diff'; export EDITOR=vim\n\
+         alias cdp='cd $PROJECT'\n\
+         alias -g L='| less'\n\
+         alias -s txt=less\n\
+         alias\n\
+         echo alias x=y\n",
+    )]);
+    let rc = read(&home, ".bashrc");
+    assert_eq!(
+        rc.aliases,
+        pairs(&[("ll", "ls -la"), ("gs", "git status"), ("gd", "git diff"), ("cdp", "cd $PROJECT")])
+    );
+    // Global and suffix aliases don't expand commands, but their names are still taken
+    assert_eq!(rc.global_aliases, pairs(&[("L", "| less"), ("txt", "less")]));
+    assert!(!rc.expansions().any(|expansion| expansion == vec!["less".to_string()]));
+    assert!(rc.names().any(|name| name == "L"));
+}
+
+#[test]
+fn fish_definitions() {
+    let home = home_with(&[(
+        "config.fish",
+        "alias ll 'ls -la'\n\
+         alias gco git checkout\n\
+         abbr -a gst git status\n\
+         abbr --add --position anywhere L '| less'\n\
+         abbr -e gst\n\
+         function mkcd\n    mkdir -p $argv[1]; and cd $argv[1]\nend\n",
+    )]);
+    let rc = read(&home, "config.fish");
+    assert_eq!(rc.aliases, pairs(&[("ll", "ls -la"), ("gco", "git checkout")]));
+    assert_eq!(rc.abbreviations, pairs(&[("gst", "git status"), ("L", "| less")]));
+    assert_eq!(rc.functions, vec!["mkcd"]);
+}
+
+#[test]
+fn functions() {
+    let home = home_with(&[(
+        ".zshrc",
+        "gs() { git status; }\nfunction mkcd {\n  mkdir -p \"$1\" && cd \"$1\"\n}\nup () {\n}\n",
+    )]);
+    assert_eq!(read(&home, ".zshrc").functions, vec!["gs", "mkcd", "up"]);
+}
+
+#[test]
+fn continued_lines() {
+    let home = home_with(&[(".bashrc", "alias deploy='make &&\n  make deploy'\nalias m=make\n")]);
+    let rc = read(&home, ".bashrc");
+    assert_eq!(rc.aliases, pairs(&[("deploy", "make &&\n  make deploy"), ("m", "make")]));
+    let expansions: Vec<(&String, Vec<String>)> = rc.alias_expansions().collect();
+    assert_eq!(expansions, vec![(&"m".to_string(), vec!["make".to_string()])]);
+}
+
+#[test]
+fn here_strings() {
+    let home = home_with(&[(
+        ".bashrc",
+        "alias gs='git status'\nread -r x <<< \"$HOME\"\nalias crr='cargo run --release'\n",
+    )]);
+    let rc = read(&home, ".bashrc");
+    assert_eq!(rc.aliases, pairs(&[("gs", "git status"), ("crr", "cargo run --release")]));
+}
+
+#[test]
+fn redefined_aliases() {
+    let home = home_with(&[(".bashrc", "alias l='ls -l'\nalias g=git\nalias l='ls -la'\n")]);
+    let rc = read(&home, ".bashrc");
+    let current: Vec<&(String, String)> = rc.current_aliases().collect();
+    assert_eq!(current, pairs(&[("g", "git"), ("l", "ls -la")]).iter().collect::<Vec<_>>());
+    let expansions: Vec<Vec<String>> = rc.expansions().collect();
+    assert_eq!(expansions, vec![strings(&["git"]), strings(&["ls", "-la"])]);
+    assert_eq!(rc.names().collect::<Vec<_>>(), vec!["g", "l"]);
+}
+
+#[test]
+fn sourced_files() {
+    let home = home_with(&[
+        (".bashrc", "[ -f ~/.aliases ] && . ~/.aliases\nsource $HOME/.bashrc\nsource missing\n"),
+        (".aliases", "alias gs='git status'\nsource conf/more.sh\nsource $ZSH/x.sh\n"),
+        ("conf/more.sh", "alias gd='git diff'\n"),
+    ]);
+    let rc = read(&home, ".bashrc");
+    assert_eq!(rc.aliases, pairs(&[("gs", "git status"), ("gd", "git diff")]));
+    let names: Vec<&String> = rc.names().collect();
+    assert_eq!(names, vec!["gs", "gd"]);
+
+    let mut missing = StartupFiles::default();
+    assert!(missing.read(&home.path().join("missing"), None).is_err());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn unreadable_sourced_files() {
+    // `/proc/self/mem` is a file, but reading it from the start fails
+    let home = home_with(&[(".bashrc", "source /proc/self/mem\nalias gs='git status'\n")]);
+    let rc = read(&home, ".bashrc");
+    assert_eq!(rc.aliases, pairs(&[("gs", "git status")]));
+    let unreadable: Vec<&PathBuf> = rc.unreadable.iter().map(|(path, _)| path).collect();
+    assert_eq!(unreadable, vec![&PathBuf::from("/proc/self/mem")]);
+}
+
+#[test]
+fn locate_startup_files() {
+    let home = home_with(&[(".bashrc", ""), (".config/fish/config.fish", "")]);
+    let env = |shell: Option<&str>| Environment {
+        shell: shell.map(PathBuf::from),
+        home: Some(home.path().to_owned()),
+        ..Environment::default()
+    };
+    assert_eq!(locate(&env(Some("/bin/bash"))), vec![home.path().join(".bashrc")]);
+    assert!(locate(&env(Some("/bin/zsh"))).is_empty());
+    assert_eq!(
+        locate(&env(None)),
+        vec![home.path().join(".bashrc"), home.path().join(".config/fish/config.fish")]
+    );
+}
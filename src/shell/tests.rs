@@ -0,0 +1,39 @@
+use super::Shell;
+
+#[test]
+fn parsing() {
+    assert_eq!("bash".parse(), Ok(Shell::Bash));
+    assert_eq!("ZSH".parse(), Ok(Shell::Zsh));
+    assert_eq!("fish".parse(), Ok(Shell::Fish));
+    assert!("csh".parse::<Shell>().is_err());
+    for name in Shell::VARIANTS {
+        assert_eq!(name.parse::<Shell>().unwrap().to_string(), *name);
+    }
+}
+
+#[test]
+fn quoting() {
+    assert_eq!(Shell::Bash.quote("ls"), "'ls'");
+    assert_eq!(Shell::Zsh.quote("echo 'hi'"), r"'echo '\''hi'\'''");
+    assert_eq!(Shell::Fish.quote(r"echo 'hi' \n"), r"'echo \'hi\' \\n'");
+}
+
+#[test]
+fn aliases() {
+    assert_eq!(Shell::Bash.alias("gs", "git status"), "alias gs='git status'");
+    assert_eq!(Shell::Zsh.alias("gs", "git status"), "alias gs='git status'");
+    assert_eq!(Shell::Fish.alias("gs", "git status"), "abbr -a gs 'git status'");
+}
+
+#[test]
+fn functions() {
+    let body = |shell: Shell| format!("git commit -m {}", shell.argument(1));
+    assert_eq!(
+        Shell::Bash.function("gc", &body(Shell::Bash)),
+        r#"gc() { git commit -m "$1"; }"#
+    );
+    assert_eq!(
+        Shell::Fish.function("gc", &body(Shell::Fish)),
+        "function gc; git commit -m $argv[1]; end"
+    );
+}
@@ -0,0 +1,110 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::iter;
+
+use prettytable::Table;
+
+use crate::history::ExecutedCommand;
+use crate::normalize::Normalizer;
+use crate::shell::rc::StartupFiles;
+use crate::trie::Trie;
+
+use super::executions::Executions;
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Represents how an alias defined in a startup file was used
+#[derive(Debug, Eq, PartialEq)]
+pub struct AliasUsage {
+    pub name: String,
+    /// The command the alias expands to, as defined
+    pub command: String,
+    /// Executions of the alias itself
+    pub uses: Executions,
+    /// Executions of the command typed out in full instead of the alias
+    pub longhand: Executions,
+}
+
+impl AliasUsage {
+    /// Produces the number of extra characters typed by running the command in full instead of
+    /// through the alias
+    pub fn lost_keystrokes(&self) -> usize {
+        let extra = self.command.len().saturating_sub(self.name.len());
+        self.longhand.count as usize * extra
+    }
+}
+
+impl Ord for AliasUsage {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.lost_keystrokes()
+            .cmp(&other.lost_keystrokes())
+            .then(self.longhand.cmp(&other.longhand))
+            .then(other.name.cmp(&self.name))
+    }
+}
+
+impl PartialOrd for AliasUsage {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Produces how each alias of `rc` was used by the commands, costliest habits first
+///
+/// Commands are counted as they were typed, without expanding aliases, so that uses of an alias
+/// are told apart from its command typed in full. Commands are only counted as typed in full for
+/// aliases of a single command that don't shadow it, like `ls='ls -G'`.
+pub fn audit<I>(commands: I, rc: &StartupFiles, normalizer: &Normalizer) -> Vec<AliasUsage>
+    where
+        I: IntoIterator<Item=ExecutedCommand>,
+{
+    let literal = Normalizer { aliases: HashMap::new(), ..normalizer.clone() };
+    let trie = Trie::from_commands(commands, &literal);
+    let expansions: HashMap<&String, Vec<String>> = rc
+        .alias_expansions()
+        .map(|(name, args)| (name, literal.normalize_expansion(args)))
+        .collect();
+    let mut usages: Vec<AliasUsage> = rc
+        .current_aliases()
+        .map(|(name, command)| {
+            let longhand = expansions
+                .get(name)
+                .filter(|args| args.first() != Some(name))
+                .and_then(|args| trie.get(args))
+                .cloned()
+                .unwrap_or_default();
+            AliasUsage {
+                name: name.to_owned(),
+                command: command.to_owned(),
+                uses: trie.get(iter::once(name)).cloned().unwrap_or_default(),
+                longhand,
+            }
+        })
+        .collect();
+    usages.sort_by(|a, b| b.cmp(a));
+    usages
+}
+
+/// Converts alias usages into a table of the aliased commands typed in full, followed by a table
+/// of the aliases never used
+pub fn build_tables(usages: Vec<AliasUsage>) -> Vec<Table> {
+    let mut habits = table!(["Typed in Full", "Command", "Alias", "Alias Uses", "Keystrokes Lost"]);
+    let mut unused = table!(["Unused Alias", "Command"]);
+    for usage in usages {
+        if usage.longhand.count > 0 {
+            habits.add_row(row![
+                usage.longhand.count,
+                &usage.command,
+                &usage.name,
+                usage.uses.count,
+                usage.lost_keystrokes(),
+            ]);
+        }
+        if usage.uses.count == 0 {
+            unused.add_row(row![usage.name, usage.command]);
+        }
+    }
+    vec![habits, unused]
+}
@@ -0,0 +1,65 @@
+use std::iter::FromIterator;
+
+use crate::history::History;
+use crate::normalize::Normalizer;
+use crate::shell::rc::StartupFiles;
+
+use super::{AliasUsage, audit, build_tables};
+
+fn sample_rc() -> StartupFiles {
+    let mut rc = StartupFiles::default();
+    rc.aliases = vec![
+        ("gs".to_string(), "git status".to_string()),
+        ("ll".to_string(), "ls -la".to_string()),
+        ("ls".to_string(), "ls -G".to_string()),
+        ("deploy".to_string(), "make && make deploy".to_string()),
+        ("gs".to_string(), "git status -s".to_string()),
+    ];
+    rc
+}
+
+fn sample_audit() -> Vec<AliasUsage> {
+    let hist = History::from_iter(&[
+        "git status -s",
+        "gs",
+        "git status -s",
+        "sudo git status -s --branch",
+        "ls -G src",
+        "ls",
+        "ls -la",
+    ]);
+    let mut normalizer = Normalizer::default();
+    normalizer.aliases.insert("ll".to_string(), vec!["ls".to_string(), "-la".to_string()]);
+    audit(hist.commands, &sample_rc(), &normalizer)
+}
+
+#[test]
+fn alias_usages() {
+    let usages = sample_audit();
+    let summary: Vec<(&str, u32, u32, usize)> = usages
+        .iter()
+        .map(|usage| {
+            (usage.name.as_str(), usage.uses.count, usage.longhand.count, usage.lost_keystrokes())
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![("gs", 1, 3, 33), ("ll", 0, 1, 4), ("deploy", 0, 0, 0), ("ls", 3, 0, 0)]
+    );
+    assert_eq!(usages[0].command, "git status -s");
+}
+
+#[test]
+fn table_building() {
+    let expected_habits = table!(
+        ["Typed in Full", "Command", "Alias", "Alias Uses", "Keystrokes Lost"],
+        [3, "git status -s", "gs", 1, 33],
+        [1, "ls -la", "ll", 0, 4]
+    );
+    let expected_unused = table!(
+        ["Unused Alias", "Command"],
+        ["ll", "ls -la"],
+        ["deploy", "make && make deploy"]
+    );
+    assert_eq!(build_tables(sample_audit()), vec![expected_habits, expected_unused]);
+}
@@ -1,36 +1,96 @@
 use std::cmp::{max, Ordering};
 
-use chrono::{DateTime, offset::Local, TimeZone};
+use chrono::{DateTime, Duration, offset::Local, Utc};
 
 #[cfg(test)]
 #[cfg_attr(tarpaulin, skip)]
 mod tests;
 
 /// Represents the executions of a command
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Executions {
     /// How many times the command has been executed
     pub count: u32,
     /// The time when the command was last executed
-    pub last_executed: Option<u32>,
+    pub last_executed: Option<DateTime<Utc>>,
+    /// Total number of seconds spent running the command, across executions with a known duration
+    pub total_duration: u64,
+    /// How many executions of the command have a known duration
+    pub timed_count: u32,
+    /// The wrapper, like `sudo`, that every execution of the command was run under, if any
+    pub wrapper: Option<Vec<String>>,
+    /// How the command was spelled before its flags were canonicalized, and how often
+    pub spellings: Vec<(Vec<String>, u32)>,
 }
 
 impl Executions {
-    /// Produces updated struct incorporating an additional execution
-    pub fn update(&self, time: Option<u32>) -> Self {
-        Executions {
-            count: self.count + 1,
-            last_executed: self
-                .last_executed
-                .map(|old| time.map_or(old, |new| max(old, new)))
-                .or(time),
+    /// Incorporates an additional execution
+    pub fn update(
+        &mut self,
+        time: Option<DateTime<Utc>>,
+        duration: Option<u32>,
+        wrapper: &[String],
+    ) {
+        let shared_wrapper = if self.count == 0 {
+            !wrapper.is_empty()
+        } else {
+            self.wrapper.as_deref() == Some(wrapper)
+        };
+        self.count += 1;
+        self.last_executed = self
+            .last_executed
+            .map(|old| time.map_or(old, |new| max(old, new)))
+            .or(time);
+        self.total_duration += duration.map_or(0, u64::from);
+        self.timed_count += duration.map_or(0, |_| 1);
+        if !shared_wrapper {
+            self.wrapper = None;
+        } else if self.wrapper.is_none() {
+            self.wrapper = Some(wrapper.to_vec());
         }
     }
 
-    /// Produces the `NaiveDateTime` at which the command was last executed
+    /// Counts another execution spelled as `spelling`
+    pub fn add_spelling(&mut self, spelling: &[String]) {
+        match self.spellings.iter_mut().find(|(known, _)| known.as_slice() == spelling) {
+            Some((_, count)) => *count += 1,
+            None => self.spellings.push((spelling.to_vec(), 1)),
+        }
+    }
+
+    /// Produces the most common spelling of the command, preferring the earliest seen on ties
+    pub fn spelling(&self) -> Option<&[String]> {
+        self.spellings
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| count)
+            .map(|(spelling, _)| spelling.as_slice())
+    }
+
+    /// Produces the average number of seconds an execution of the command took
+    pub fn mean_duration(&self) -> Option<f64> {
+        if self.timed_count == 0 {
+            None
+        } else {
+            Some(self.total_duration as f64 / f64::from(self.timed_count))
+        }
+    }
+
+    /// Produces a weight for the executions that halves every `half_life` between when the command
+    /// was last executed and `latest`, or 1 if either is unknown
+    pub fn recency_weight(&self, latest: Option<DateTime<Utc>>, half_life: Duration) -> f64 {
+        match (self.last_executed, latest) {
+            (Some(last), Some(latest)) if half_life > Duration::zero() => {
+                let age = latest.signed_duration_since(last).max(Duration::zero());
+                0.5f64.powf(age.num_seconds() as f64 / half_life.num_seconds() as f64)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Produces the local time at which the command was last executed
     pub fn last_executed(&self) -> Option<DateTime<Local>> {
-        self.last_executed
-            .map(|time| Local.timestamp(time as i64, 0))
+        self.last_executed.map(|time| time.with_timezone(&Local))
     }
 
     /// Produces a human readable time at which the command was last executed
@@ -48,6 +108,18 @@ impl Ord for Executions {
 
 impl PartialOrd for Executions {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(&other))
+        Some(self.cmp(other))
+    }
+}
+
+/// Formats a number of seconds as a human readable duration, e.g. `1h 02m 05s`
+pub fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
     }
 }
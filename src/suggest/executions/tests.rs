@@ -1,6 +1,19 @@
-use chrono::{Local, TimeZone};
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
 
-use super::Executions;
+use crate::testing::{at, strings};
+
+use super::{Executions, format_duration};
+
+fn updated(
+    executions: &Executions,
+    time: Option<DateTime<Utc>>,
+    duration: Option<u32>,
+    wrapper: &[String],
+) -> Executions {
+    let mut executions = executions.clone();
+    executions.update(time, duration, wrapper);
+    executions
+}
 
 #[test]
 fn default() {
@@ -9,6 +22,10 @@ fn default() {
         Executions {
             count: 0,
             last_executed: None,
+            total_duration: 0,
+            timed_count: 0,
+            wrapper: None,
+            spellings: Vec::new(),
         }
     );
 }
@@ -17,17 +34,19 @@ fn default() {
 fn updating_no_last_executed() {
     let base = Executions::default();
     assert_eq!(
-        base.update(Some(5)),
+        updated(&base, at(5), None, &[]),
         Executions {
             count: 1,
-            last_executed: Some(5),
+            last_executed: at(5),
+            ..Executions::default()
         }
     );
     assert_eq!(
-        base.update(None),
+        updated(&base, None, None, &[]),
         Executions {
             count: 1,
             last_executed: None,
+            ..Executions::default()
         }
     );
 }
@@ -36,27 +55,31 @@ fn updating_no_last_executed() {
 fn updating_existing_last_executed() {
     let base = Executions {
         count: 1,
-        last_executed: Some(5),
+        last_executed: at(5),
+        ..Executions::default()
     };
     assert_eq!(
-        base.update(None),
+        updated(&base, None, None, &[]),
         Executions {
             count: 2,
-            last_executed: Some(5),
+            last_executed: at(5),
+            ..Executions::default()
         }
     );
     assert_eq!(
-        base.update(Some(3)),
+        updated(&base, at(3), None, &[]),
         Executions {
             count: 2,
-            last_executed: Some(5),
+            last_executed: at(5),
+            ..Executions::default()
         }
     );
     assert_eq!(
-        base.update(Some(6)),
+        updated(&base, at(6), None, &[]),
         Executions {
             count: 2,
-            last_executed: Some(6),
+            last_executed: at(6),
+            ..Executions::default()
         }
     );
 }
@@ -65,8 +88,69 @@ fn updating_existing_last_executed() {
 fn representation() {
     let executions = Executions {
         count: 1,
-        last_executed: Some(1565737322),
+        last_executed: at(1565737322),
+        ..Executions::default()
     };
     assert_eq!(executions.last_executed().unwrap(), Local.timestamp(1565737322, 0));
     assert!(executions.last_executed_str().unwrap().starts_with("2019-08"));
-}
\ No newline at end of file
+}
+#[test]
+fn updating_durations() {
+    let base = Executions::default();
+    assert_eq!(base.mean_duration(), None);
+
+    let mut timed = base;
+    timed.update(None, Some(3), &[]);
+    timed.update(None, None, &[]);
+    timed.update(None, Some(6), &[]);
+    assert_eq!(timed.count, 3);
+    assert_eq!(timed.total_duration, 9);
+    assert_eq!(timed.timed_count, 2);
+    assert_eq!(timed.mean_duration(), Some(4.5));
+}
+
+#[test]
+fn updating_wrappers() {
+    let sudo = vec!["sudo".to_string()];
+    let wrapped = updated(&Executions::default(), None, None, &sudo);
+    assert_eq!(wrapped.wrapper, Some(sudo.clone()));
+    assert_eq!(updated(&wrapped, None, None, &sudo).wrapper, Some(sudo.clone()));
+    assert_eq!(updated(&wrapped, None, None, &[]).wrapper, None);
+
+    let bare = updated(&Executions::default(), None, None, &[]);
+    assert_eq!(bare.wrapper, None);
+    assert_eq!(updated(&bare, None, None, &sudo).wrapper, None);
+}
+
+#[test]
+fn counting_spellings() {
+    let mut executions = Executions::default();
+    assert_eq!(executions.spelling(), None);
+
+    executions.add_spelling(&strings(&["ls", "-la"]));
+    executions.add_spelling(&strings(&["ls", "-al"]));
+    assert_eq!(executions.spelling(), Some(&strings(&["ls", "-la"])[..]));
+    executions.add_spelling(&strings(&["ls", "-al"]));
+    assert_eq!(executions.spelling(), Some(&strings(&["ls", "-al"])[..]));
+    assert_eq!(executions.spellings.len(), 2);
+}
+
+#[test]
+fn recency_weights() {
+    let day = Duration::days(1);
+    let executions = updated(&Executions::default(), at(0), None, &[]);
+    assert_eq!(executions.recency_weight(at(0), day), 1.0);
+    assert_eq!(executions.recency_weight(at(86400), day), 0.5);
+    assert_eq!(executions.recency_weight(at(3 * 86400), day), 0.125);
+    assert_eq!(executions.recency_weight(None, day), 1.0);
+    assert_eq!(executions.recency_weight(at(86400), Duration::zero()), 1.0);
+    assert_eq!(Executions::default().recency_weight(at(86400), day), 1.0);
+}
+
+#[test]
+fn duration_formatting() {
+    assert_eq!(format_duration(0), "0s");
+    assert_eq!(format_duration(59), "59s");
+    assert_eq!(format_duration(65), "1m 05s");
+    assert_eq!(format_duration(3725), "1h 02m 05s");
+}
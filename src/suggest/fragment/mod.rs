@@ -0,0 +1,210 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use prettytable::Table;
+
+use crate::capped_heap::CappedHeap;
+use crate::history::{ExecutedCommand, quote};
+use crate::normalize::{NormalizedCommand, Normalizer};
+use crate::shell::{names, Shell};
+
+use super::executions::Executions;
+use super::{Miner, subsumed};
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Most words counted in a single fragment
+const MAX_WORDS: usize = 4;
+/// Fewest executions a fragment must appear in to be suggested
+const MIN_USES: u32 = 2;
+/// Operators passing the output of one command to the next
+const PIPES: &[&str] = &["|", "|&"];
+/// Operators separating commands, which fragments never span
+const SEPARATORS: &[&str] = &["&&", "||", ";", "&"];
+
+/// Represents a repeated fragment of commands that doesn't start at the command itself
+///
+/// e.g. `--hidden --follow` in `rg --files --hidden --follow`, or `| less -R` in whole lines
+#[derive(Debug, Eq, PartialEq)]
+pub struct Fragment {
+    /// Name of the global alias, abbreviation or variable, if one is free
+    pub name: Option<String>,
+    pub words: Vec<String>,
+    pub executions: Executions,
+}
+
+impl Fragment {
+    /// Produces the fragment as it would be typed, leaving pipes unquoted
+    pub fn text(&self) -> String {
+        let words = self.words.iter().map(|word| {
+            if is_pipe(word) { word.to_owned() } else { quote(word).into_owned() }
+        });
+        words.collect::<Vec<_>>().join(" ")
+    }
+
+    /// Produces candidate names for the fragment from the initials of its words, in upper case
+    /// like variables, e.g. `HF` for `--hidden --follow`
+    pub fn candidates(&self) -> Vec<String> {
+        names::candidates(&self.words).iter().map(|name| name.to_uppercase()).collect()
+    }
+
+    /// Produces a zsh global alias expanding to the fragment anywhere in a command, if it has a
+    /// name
+    pub fn global_alias(&self) -> Option<String> {
+        let name = self.name.as_ref()?;
+        Some(format!("alias -g {}={}", name, Shell::Zsh.quote(&self.text())))
+    }
+
+    /// Produces a variable holding the fragment, e.g. for `rg --files $HF` in bash, if it has a
+    /// name and doesn't contain pipes, redirections or quotes, which aren't parsed in expanded
+    /// variables
+    pub fn variable(&self) -> Option<String> {
+        let name = self.name.as_ref()?;
+        let is_plain = |word: &String| !is_pipe(word) && !is_redirect(word) && quote(word) == *word;
+        if self.words.iter().all(is_plain) {
+            Some(format!("{}={}", name, Shell::Bash.quote(&self.text())))
+        } else {
+            None
+        }
+    }
+
+    /// Produces a definition expanding to the fragment anywhere in a command in `shell`, which is
+    /// a global alias in zsh, an abbreviation in fish and a variable in bash, if possible
+    pub fn definition(&self, shell: Shell) -> Option<String> {
+        match shell {
+            Shell::Zsh => self.global_alias(),
+            Shell::Fish => Some(format!(
+                "abbr -a --position anywhere {} {}",
+                self.name.as_ref()?,
+                shell.quote(&self.text())
+            )),
+            Shell::Bash => self.variable(),
+        }
+    }
+
+    /// Produces the number of characters typed for the fragment
+    fn len(&self) -> usize {
+        self.text().len()
+    }
+}
+
+impl Ord for Fragment {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let typed = |fragment: &Self| fragment.executions.count as usize * fragment.len();
+        typed(self)
+            .cmp(&typed(other))
+            .then(self.executions.cmp(&other.executions))
+            .then(other.words.cmp(&self.words))
+    }
+}
+
+impl PartialOrd for Fragment {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Determines whether a word is a pipe operator
+fn is_pipe(word: &str) -> bool {
+    PIPES.contains(&word)
+}
+
+/// Determines whether a word is a redirection operator like `2>&1` or `>`
+fn is_redirect(word: &str) -> bool {
+    let redirect = word.trim_start_matches(|c: char| c.is_ascii_digit());
+    redirect.starts_with('>') || redirect.starts_with('<')
+}
+
+/// Determines whether a fragment may start with a word, which is the case for flags, pipes and
+/// redirections
+fn starts_fragment(word: &str) -> bool {
+    (word.starts_with('-') && word.len() > 1) || is_pipe(word) || is_redirect(word)
+}
+
+/// Produces the distinct fragments of a command's arguments, which start after the command
+/// itself and span at least two words
+fn fragments(args: &[String]) -> HashSet<&[String]> {
+    let mut fragments = HashSet::new();
+    for start in (1..args.len()).filter(|&start| starts_fragment(&args[start])) {
+        let words = args[start..]
+            .iter()
+            .take(MAX_WORDS)
+            .take_while(|word| !SEPARATORS.contains(&word.as_str()))
+            .count();
+        for end in start + 2..=start + words {
+            fragments.insert(&args[start..end]);
+        }
+    }
+    fragments
+}
+
+/// Mines commands for repeated fragments that don't start at the command itself, which the
+/// prefixes counted for suggestions can't find
+pub struct FragmentMiner<'a> {
+    normalizer: &'a Normalizer,
+    uses: HashMap<Vec<String>, Executions>,
+}
+
+impl<'a> FragmentMiner<'a> {
+    /// Initializes a `FragmentMiner` normalizing commands with `normalizer`
+    pub fn new(normalizer: &'a Normalizer) -> Self {
+        FragmentMiner {
+            normalizer,
+            uses: HashMap::new(),
+        }
+    }
+
+    /// Produces an iterator of the `n` most useful fragments counted
+    ///
+    /// Fragments used at least `MIN_USES` times are included, unless a longer fragment containing
+    /// them was used just as often.
+    pub fn into_fragments(self, n: usize) -> impl Iterator<Item=Fragment> {
+        let subsumed = subsumed(&self.uses);
+        let mut heap = CappedHeap::new(n);
+        for (words, executions) in &self.uses {
+            let fragment = Fragment {
+                name: None,
+                words: words.to_owned(),
+                executions: executions.clone(),
+            };
+            let is_nameable = !fragment.candidates().is_empty();
+            if executions.count >= MIN_USES && is_nameable && !subsumed.contains(&words[..]) {
+                heap.insert(fragment);
+            }
+        }
+        heap.heap.into_vec_desc().into_iter()
+    }
+}
+
+impl<'a> Miner for FragmentMiner<'a> {
+    /// Counts an execution of a command
+    fn add(&mut self, command: ExecutedCommand) {
+        let NormalizedCommand { args, time, .. } = self.normalizer.normalize(command);
+        for fragment in fragments(&args) {
+            self.uses.entry(fragment.to_vec()).or_default().update(time, None, &[]);
+        }
+    }
+}
+
+/// Converts an iterator of fragments into a table
+pub fn build_table<I>(fragments: I) -> Table
+    where
+        I: IntoIterator<Item=Fragment>,
+{
+    let mut table = table!(["Uses", "Last Used", "Fragment", "Global Alias", "Variable"]);
+    for fragment in fragments {
+        table.add_row(row![
+            fragment.executions.count,
+            fragment
+                .executions
+                .last_executed_str()
+                .unwrap_or("Unknown".to_string()),
+            fragment.text(),
+            fragment.global_alias().unwrap_or_default(),
+            fragment.variable().unwrap_or_default(),
+        ]);
+    }
+    table
+}
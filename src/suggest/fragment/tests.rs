@@ -0,0 +1,107 @@
+use std::iter::FromIterator;
+
+use crate::history::{History, ParseOptions};
+use crate::normalize::Normalizer;
+use crate::shell::names::Namer;
+use crate::shell::Shell;
+use crate::testing::{self, strings};
+
+use super::{build_table, Fragment, FragmentMiner, fragments};
+
+fn mine(hist: History, n: usize) -> Vec<Fragment> {
+    let normalizer = Normalizer::default();
+    let mut namer = Namer::new();
+    let mut fragments: Vec<Fragment> =
+        testing::mine(FragmentMiner::new(&normalizer), hist).into_fragments(n).collect();
+    for fragment in &mut fragments {
+        fragment.name = namer.choose(fragment.candidates());
+    }
+    fragments
+}
+
+fn texts(fragments: &[Fragment]) -> Vec<String> {
+    fragments.iter().map(Fragment::text).collect()
+}
+
+#[test]
+fn command_fragments() {
+    let args = strings(&["rg", "--files", "--hidden", "src", "&&", "ls"]);
+    let mut fragments: Vec<Vec<String>> = fragments(&args).into_iter().map(<[_]>::to_vec).collect();
+    fragments.sort();
+    assert_eq!(
+        fragments,
+        vec![
+            strings(&["--files", "--hidden"]),
+            strings(&["--files", "--hidden", "src"]),
+            strings(&["--hidden", "src"]),
+        ]
+    );
+    assert!(super::fragments(&strings(&["cargo", "run", "--release"])).is_empty());
+}
+
+#[test]
+fn flag_tails() {
+    let hist = History::from_iter(&[
+        "rg --files --hidden --follow",
+        "rg foo --hidden --follow",
+        "fd --hidden --follow -e rs",
+        "cargo run --release",
+    ]);
+    let fragments = mine(hist, 5);
+    assert_eq!(texts(&fragments), vec!["--hidden --follow"]);
+    assert_eq!(fragments[0].executions.count, 3);
+    assert_eq!(fragments[0].global_alias(), Some("alias -g HF='--hidden --follow'".to_string()));
+    assert_eq!(fragments[0].variable(), Some("HF='--hidden --follow'".to_string()));
+    assert_eq!(fragments[0].definition(Shell::Bash), fragments[0].variable());
+    assert_eq!(fragments[0].definition(Shell::Zsh), fragments[0].global_alias());
+}
+
+#[test]
+fn distinct_names() {
+    let hist = History::from_iter(&[
+        "rg --hidden --follow",
+        "rg --hidden --follow",
+        "ls --human --full",
+        "ls --human --full",
+    ]);
+    let fragments = mine(hist, 5);
+    let mut names: Vec<_> = fragments.iter().filter_map(|fragment| fragment.name.clone()).collect();
+    names.sort();
+    assert_eq!(names, vec!["HF", "HFU"]);
+
+    let mut fragment = mine(History::from_iter(&["ls -l -a", "ls -l -a"]), 1).remove(0);
+    fragment.name = None;
+    assert_eq!(fragment.global_alias(), None);
+    assert_eq!(fragment.definition(Shell::Fish), None);
+}
+
+#[test]
+fn pipeline_tails() {
+    let input = "git log | less -R\ncat notes | less -R\nls | grep -v test | less -R\n";
+    let whole_lines = ParseOptions {
+        whole_lines: true,
+        ..ParseOptions::default()
+    };
+    let fragments = mine(History::parse(input.lines(), whole_lines), 5);
+    assert_eq!(texts(&fragments), vec!["| less -R"]);
+    assert_eq!(fragments[0].global_alias(), Some("alias -g LR='| less -R'".to_string()));
+    assert_eq!(fragments[0].variable(), None);
+    assert_eq!(fragments[0].definition(Shell::Bash), None);
+    assert_eq!(
+        fragments[0].definition(Shell::Fish),
+        Some("abbr -a --position anywhere LR '| less -R'".to_string())
+    );
+
+    let fragments = mine(History::from_iter(input.lines()), 5);
+    assert!(fragments.is_empty());
+}
+
+#[test]
+fn table_building() {
+    let hist = History::from_iter(&["cmake .. -G Ninja", "cmake src -G Ninja"]);
+    let expected = table!(
+        ["Uses", "Last Used", "Fragment", "Global Alias", "Variable"],
+        [2, "Unknown", "-G Ninja", "alias -g GN='-G Ninja'", "GN='-G Ninja'"]
+    );
+    assert_eq!(build_table(mine(hist, 5)), expected);
+}
@@ -1,55 +1,168 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use chrono::Duration;
 use prettytable::Table;
 
 use executions::Executions;
+use rank::{Ranked, Ranker};
 use suggestion::Suggestion;
 
 use crate::capped_heap::CappedHeap;
-use crate::history::{ExecutedCommand, History};
+use crate::history::ExecutedCommand;
+use crate::normalize::{NormalizedCommand, Normalizer};
+use crate::shell::names::Namer;
 use crate::trie::Trie;
 
+pub mod audit;
+pub mod fragment;
+pub mod pattern;
+pub mod rank;
+pub mod sequence;
+pub mod slow;
 mod suggestion;
 mod executions;
 #[cfg(test)]
 #[cfg_attr(tarpaulin, skip)]
 mod tests;
 
+/// Mines commands for suggestions other than aliases of their prefixes
+pub trait Miner {
+    /// Counts an execution of a command
+    fn add(&mut self, command: ExecutedCommand);
+}
+
+/// Produces the runs of items that are subsumed by a run one item longer, which contains them
+/// and was used just as often
+///
+/// e.g. `--hidden --follow` is subsumed by `--hidden --follow --glob` if they were always used
+/// together.
+fn subsumed<T: Eq + Hash>(uses: &HashMap<Vec<T>, Executions>) -> HashSet<&[T]> {
+    let count = |items: &[T]| uses.get(items).map_or(0, |uses| uses.count);
+    uses.iter()
+        .flat_map(|(items, uses)| {
+            let shorter = vec![&items[1..], &items[..items.len() - 1]];
+            shorter.into_iter().filter(move |shorter| count(shorter) == uses.count)
+        })
+        .collect()
+}
+
+/// How suggestions are ranked and named
+pub struct Ranking<'a> {
+    /// Time over which the score of a command halves, counted back from the latest command
+    pub half_life: Option<Duration>,
+    /// Orders the suggestions
+    pub ranker: &'a dyn Ranker,
+    /// Chooses the names of the suggestions
+    pub namer: &'a mut Namer,
+}
+
+/// Determines whether a prefix counted by `normalizer` was typed, which it may not have been if
+/// flags were canonicalized, e.g. `ls -a` is a prefix of `ls -la` once it's expanded to
+/// `ls -a -l`
+fn was_typed(executions: &Executions, normalizer: &Normalizer) -> bool {
+    !normalizer.canonical_flags || executions.spelling().is_some()
+}
+
 impl Trie<String, Executions> {
-    /// Produces a list of at most `n` `Suggestion`s sorted in descending order by value
-    fn drain_top_items(mut self, n: usize) -> Vec<Suggestion> {
+    /// Builds a trie counting the executions of each prefix of the normalized commands
+    ///
+    /// e.g. `cargo run` counts as a usage of `cargo run` and `cargo`
+    fn from_commands<I>(commands: I, normalizer: &Normalizer) -> Self
+        where
+            I: IntoIterator<Item=ExecutedCommand>,
+    {
+        let mut trie = Trie::new();
+        for parsed in commands {
+            let command = normalizer.normalize(parsed);
+            let NormalizedCommand { args, wrapper, time, duration, .. } = &command;
+            trie.update_path(args, |depth, uses: &mut Executions| {
+                uses.update(*time, *duration, wrapper);
+                if let Some(spelling) = command.spelling(depth) {
+                    uses.add_spelling(spelling);
+                }
+            });
+        }
+        trie
+    }
+
+    /// Produces a list of at most `n` items sorted in descending order
+    ///
+    /// Items are created from the arguments and executions of each prefix, which is skipped if
+    /// `item` produces `None`.
+    fn drain_top_items<T, F>(mut self, n: usize, item: F) -> Vec<T>
+        where
+            T: Ord,
+            F: Fn(Vec<String>, Executions) -> Option<T>,
+    {
         let mut heap = CappedHeap::new(n);
         for (arg, node) in self.children.drain() {
-            node.add_to_heap(vec![arg], &mut heap);
+            node.add_to_heap(vec![arg], &mut heap, &item);
         }
         heap.heap.into_vec_desc()
     }
 
     /// Adds the current node and its children to a `CappedHeap`
-    fn add_to_heap(mut self, args: Vec<String>, heap: &mut CappedHeap<Suggestion>) {
+    fn add_to_heap<T, F>(mut self, args: Vec<String>, heap: &mut CappedHeap<T>, item: &F)
+        where
+            T: Ord,
+            F: Fn(Vec<String>, Executions) -> Option<T>,
+    {
         // Add current node to the heap
-        let suggestion = Suggestion::new(args.to_owned(), self.value);
-        heap.insert(suggestion);
+        if let Some(item) = item(args.to_owned(), self.value) {
+            heap.insert(item);
+        }
 
         // Add children to the heap
         for (arg, node) in self.children.drain() {
             let mut args = args.to_owned();
             args.push(arg);
-            node.add_to_heap(args, heap);
+            node.add_to_heap(args, heap, item);
         }
     }
 }
 
-/// Produces an iterator of `n` suggested commands to alias
-pub fn suggest(history: History, n: usize) -> impl Iterator<Item=Suggestion> {
-    // Insert commands into trie, counting the frequency with which prefixes of commands are used
-    // e.g. `cargo run` counts as a usage of `cargo run` and `cargo`
-    let mut trie: Trie<String, Executions> = Trie::new();
-    for parsed in history.commands {
-        let ExecutedCommand { args, time } = parsed;
-        trie.update_path(args, |uses| uses.update(time));
+/// Produces an iterator of the `n` suggested commands to alias that the `ranking`'s ranker ranks
+/// highest, named by its namer
+///
+/// Commands are counted as they are produced, so they needn't all be kept in memory. Commands
+/// in `covered`, like those already aliased, aren't suggested. If a half-life is given, the score
+/// of a command halves every half-life between its last use and the latest command.
+///
+/// Commands are ranked by the names the namer would choose for them, and named in that order.
+/// When a better suggestion takes the name another was ranked by, the other is ranked again by
+/// its own name, though only among the `n` suggestions already chosen.
+pub fn suggest<I>(
+    commands: I,
+    n: usize,
+    normalizer: &Normalizer,
+    covered: &HashSet<Vec<String>>,
+    ranking: Ranking<'_>,
+) -> impl Iterator<Item=Suggestion>
+    where
+        I: IntoIterator<Item=ExecutedCommand>,
+{
+    let Ranking { half_life, ranker, namer } = ranking;
+    let trie = Trie::from_commands(commands, normalizer);
+    let latest = trie.value.last_executed;
+    let names: &Namer = namer;
+    let mut suggestions = trie.drain_top_items(n, |args, executions| {
+        if covered.contains(&args) || !was_typed(&executions, normalizer) {
+            return None;
+        }
+        let mut suggestion = Suggestion::new(args, executions);
+        suggestion.name = names.peek(suggestion.candidates());
+        if let Some(half_life) = half_life {
+            suggestion.weight = suggestion.executions.recency_weight(latest, half_life);
+        }
+        Some(Ranked::new(suggestion, ranker))
+    });
+    for ranked in &mut suggestions {
+        ranked.suggestion.name = namer.choose(ranked.suggestion.candidates());
+        ranked.key = ranker.key(&ranked.suggestion);
     }
-
-    // Generate suggestions
-    trie.drain_top_items(n).into_iter().map(Suggestion::from)
+    suggestions.sort_by(|a, b| b.cmp(a));
+    suggestions.into_iter().map(|ranked| ranked.suggestion)
 }
 
 /// Converts an iterator of suggestions into a table
@@ -57,14 +170,16 @@ pub fn build_table<I>(suggestions: I) -> Table
     where
         I: IntoIterator<Item=Suggestion>,
 {
-    let mut table = table!(["Uses", "Last Used", "Command"]);
+    let mut table = table!(["Score", "Uses", "Last Used", "Name", "Command"]);
     for suggestion in suggestions {
         table.add_row(row![
+            suggestion.score(),
             suggestion.executions.count,
             suggestion
                 .executions
                 .last_executed_str()
                 .unwrap_or("Unknown".to_string()),
+            suggestion.name.unwrap_or_default(),
             suggestion.command,
         ]);
     }
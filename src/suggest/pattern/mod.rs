@@ -0,0 +1,258 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use prettytable::Table;
+
+use crate::capped_heap::CappedHeap;
+use crate::history::{ExecutedCommand, quote};
+use crate::normalize::{NormalizedCommand, Normalizer};
+use crate::shell::{names, Shell};
+
+use super::executions::Executions;
+use super::Miner;
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Arguments of a command, with `None` in place of the arguments that vary between executions
+pub type Template = Vec<Option<String>>;
+
+/// Commands with more arguments than this aren't mined for patterns
+const MAX_ARGS: usize = 8;
+/// Most arguments a single pattern replaces with placeholders
+const MAX_PLACEHOLDERS: usize = 2;
+/// Fewest distinct values an argument must take to be replaced with a placeholder
+const MIN_VALUES: usize = 3;
+/// Number of commands counted between prunings of rare templates
+const PRUNE_INTERVAL: usize = 10_000;
+
+/// Represents a suggested shell function for commands that only differ in some arguments
+///
+/// e.g. `kubectl logs -f pod-a -n prod` and `kubectl logs -f pod-b -n prod` are executions of
+/// `kl() { kubectl logs -f "$1" -n prod; }`
+#[derive(Debug, Eq, PartialEq)]
+pub struct Pattern {
+    /// Name of the function, if one is free
+    pub name: Option<String>,
+    pub template: Template,
+    pub executions: Executions,
+}
+
+impl Pattern {
+    /// Produces candidate names for the function from the words before its first placeholder,
+    /// e.g. `kl` for `kubectl logs -f "$1"`
+    pub fn candidates(&self) -> Vec<String> {
+        let words: Vec<&String> = self
+            .template
+            .iter()
+            .take_while(|arg| arg.is_some())
+            .flatten()
+            .filter(|arg| !arg.starts_with('-'))
+            .collect();
+        names::candidates(&words)
+    }
+
+    /// Produces the body of the function in `shell`, passing its arguments in place of the
+    /// placeholders
+    ///
+    /// The body includes the wrapper, like `sudo`, that all of its executions were run under.
+    pub fn body(&self, shell: Shell) -> String {
+        let wrapper = self.executions.wrapper.as_deref().unwrap_or_default();
+        let wrapper = wrapper.iter().map(|arg| quote(arg).into_owned());
+        let mut placeholders = 0;
+        let args = self.template.iter().map(|arg| match arg {
+            Some(arg) => quote(arg).into_owned(),
+            None => {
+                placeholders += 1;
+                shell.argument(placeholders)
+            }
+        });
+        wrapper.chain(args).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Produces the definition of the function in bash or zsh, if it has a name, e.g.
+    /// `kl() { kubectl logs -f "$1" -n prod; }`
+    pub fn function(&self) -> Option<String> {
+        self.definition(Shell::Bash)
+    }
+
+    /// Produces the definition of the function in `shell`, if it has a name
+    pub fn definition(&self, shell: Shell) -> Option<String> {
+        Some(shell.function(self.name.as_ref()?, &self.body(shell)))
+    }
+
+    /// Produces the number of characters of the template that aren't placeholders
+    fn literal_len(&self) -> usize {
+        self.template.iter().flatten().map(|arg| arg.len() + 1).sum()
+    }
+
+    /// Produces the number of placeholders in the template
+    fn placeholders(&self) -> usize {
+        self.template.iter().filter(|arg| arg.is_none()).count()
+    }
+}
+
+impl Ord for Pattern {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let typed = |pattern: &Self| pattern.executions.count as usize * pattern.literal_len();
+        typed(self)
+            .cmp(&typed(other))
+            // Prefer the more specific of two equally useful patterns
+            .then(other.placeholders().cmp(&self.placeholders()))
+            .then(self.executions.cmp(&other.executions))
+            .then(other.template.cmp(&self.template))
+    }
+}
+
+impl PartialOrd for Pattern {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Counts the executions of a template and the values taken by its placeholders
+#[derive(Debug, Default)]
+struct Uses {
+    executions: Executions,
+    /// Distinct values of each placeholder, up to `MIN_VALUES` of them
+    values: Vec<HashSet<String>>,
+    /// Number of prunings before the template was first counted
+    since: usize,
+}
+
+impl Uses {
+    /// Determines whether every placeholder took enough distinct values to be variable
+    fn is_variable(&self) -> bool {
+        self.values.iter().all(|values| values.len() >= MIN_VALUES)
+    }
+}
+
+/// Produces the templates a command is an execution of
+///
+/// Flags and the command itself are never replaced with placeholders, and at least one other
+/// argument is kept so that the template isn't just the command. If the shell recorded which
+/// arguments are `paths`, only they are replaced.
+fn templates(args: &[String], paths: &[String]) -> Vec<(Template, Vec<usize>)> {
+    if args.len() > MAX_ARGS {
+        return Vec::new();
+    }
+    let variable: Vec<usize> = (1..args.len())
+        .filter(|&i| !args[i].starts_with('-'))
+        .filter(|&i| paths.is_empty() || paths.contains(&args[i]))
+        .collect();
+    let mut positions: Vec<Vec<usize>> = vec![vec![]];
+    for &i in &variable {
+        let extended: Vec<Vec<usize>> = positions
+            .iter()
+            .filter(|set| set.len() < MAX_PLACEHOLDERS)
+            .map(|set| set.iter().cloned().chain(Some(i)).collect())
+            .collect();
+        positions.extend(extended);
+    }
+    positions
+        .into_iter()
+        .filter(|set| !set.is_empty() && set.len() + 1 < args.len())
+        .map(|set| {
+            let template = args
+                .iter()
+                .enumerate()
+                .map(|(i, arg)| if set.contains(&i) { None } else { Some(arg.to_owned()) })
+                .collect();
+            (template, set)
+        })
+        .collect()
+}
+
+/// Mines commands for shell functions, by counting the templates each command is an execution of
+///
+/// Arguments that take at least `MIN_VALUES` distinct values are replaced with placeholders.
+///
+/// A command is an execution of up to 28 templates, so counting all of them would take memory
+/// in proportion to the length of the history. Instead, templates used at most once per
+/// `PRUNE_INTERVAL` commands since they were first counted are forgotten every `PRUNE_INTERVAL`
+/// commands, which undercounts the remaining templates by at most one use per interval.
+pub struct PatternMiner<'a> {
+    normalizer: &'a Normalizer,
+    uses: HashMap<Template, Uses>,
+    /// Number of commands counted
+    commands: usize,
+    /// Number of times rare templates were pruned
+    prunings: usize,
+}
+
+impl<'a> PatternMiner<'a> {
+    /// Initializes a `PatternMiner` normalizing commands with `normalizer`
+    pub fn new(normalizer: &'a Normalizer) -> Self {
+        PatternMiner {
+            normalizer,
+            uses: HashMap::new(),
+            commands: 0,
+            prunings: 0,
+        }
+    }
+
+    /// Forgets the templates used at most once per `PRUNE_INTERVAL` commands since they were
+    /// first counted
+    fn prune(&mut self) {
+        self.prunings += 1;
+        let prunings = self.prunings;
+        self.uses.retain(|_, uses| uses.executions.count as usize > prunings - uses.since);
+    }
+
+    /// Produces an iterator of the `n` most useful shell functions for the commands counted
+    pub fn into_patterns(self, n: usize) -> impl Iterator<Item=Pattern> {
+        let mut heap = CappedHeap::new(n);
+        for (template, uses) in self.uses {
+            if uses.is_variable() {
+                heap.insert(Pattern { name: None, template, executions: uses.executions });
+            }
+        }
+        heap.heap.into_vec_desc().into_iter()
+    }
+}
+
+impl<'a> Miner for PatternMiner<'a> {
+    /// Counts an execution of a command
+    fn add(&mut self, command: ExecutedCommand) {
+        let NormalizedCommand { args, wrapper, time, duration, paths, .. } =
+            self.normalizer.normalize(command);
+        let since = self.prunings;
+        for (template, positions) in templates(&args, &paths) {
+            let entry = self.uses.entry(template).or_insert_with(|| Uses {
+                since,
+                ..Uses::default()
+            });
+            entry.executions.update(time, duration, &wrapper);
+            entry.values.resize_with(positions.len(), HashSet::new);
+            for (values, &i) in entry.values.iter_mut().zip(&positions) {
+                if values.len() < MIN_VALUES {
+                    values.insert(args[i].to_owned());
+                }
+            }
+        }
+        self.commands += 1;
+        if self.commands.is_multiple_of(PRUNE_INTERVAL) {
+            self.prune();
+        }
+    }
+}
+
+/// Converts an iterator of patterns into a table
+pub fn build_table<I>(patterns: I) -> Table
+    where
+        I: IntoIterator<Item=Pattern>,
+{
+    let mut table = table!(["Uses", "Last Used", "Function"]);
+    for pattern in patterns {
+        table.add_row(row![
+            pattern.executions.count,
+            pattern
+                .executions
+                .last_executed_str()
+                .unwrap_or("Unknown".to_string()),
+            pattern.function().unwrap_or_default(),
+        ]);
+    }
+    table
+}
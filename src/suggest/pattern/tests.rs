@@ -0,0 +1,157 @@
+use std::iter::FromIterator;
+
+use crate::history::History;
+use crate::normalize::Normalizer;
+use crate::shell::names::Namer;
+use crate::shell::Shell;
+use crate::testing::{mine, strings};
+
+use super::{build_table, Pattern, PatternMiner, PRUNE_INTERVAL, Template, templates};
+
+fn template(args: &[Option<&str>]) -> Template {
+    args.iter().map(|arg| arg.map(str::to_string)).collect()
+}
+
+fn sample_patterns(lines: &[&str], n: usize) -> Vec<Pattern> {
+    let normalizer = Normalizer::default();
+    let miner = mine(PatternMiner::new(&normalizer), History::from_iter(lines));
+    let mut namer = Namer::new();
+    let mut patterns: Vec<Pattern> = miner.into_patterns(n).collect();
+    for pattern in &mut patterns {
+        pattern.name = namer.choose(pattern.candidates());
+    }
+    patterns
+}
+
+#[test]
+fn command_templates() {
+    let templates: Vec<Template> = templates(&strings(&["git", "commit", "-m", "fix"]), &[])
+        .into_iter()
+        .map(|(template, _)| template)
+        .collect();
+    assert_eq!(
+        templates,
+        vec![
+            template(&[Some("git"), None, Some("-m"), Some("fix")]),
+            template(&[Some("git"), Some("commit"), Some("-m"), None]),
+            template(&[Some("git"), None, Some("-m"), None]),
+        ]
+    );
+    assert!(super::templates(&strings(&["ls", "src"]), &[]).is_empty());
+    assert!(super::templates(&strings(&["ls", "-l"]), &[]).is_empty());
+}
+
+#[test]
+fn path_templates() {
+    let args = strings(&["cp", "-r", "src", "backup"]);
+    let templates: Vec<Template> = templates(&args, &strings(&["src"]))
+        .into_iter()
+        .map(|(template, _)| template)
+        .collect();
+    assert_eq!(templates, vec![template(&[Some("cp"), Some("-r"), None, Some("backup")])]);
+}
+
+#[test]
+fn variable_arguments() {
+    let patterns = sample_patterns(
+        &[
+            "kubectl logs -f api-1 -n prod",
+            "kubectl logs -f api-2 -n prod",
+            "kubectl logs -f web-1 -n prod",
+            "kubectl logs -f web-1 -n prod",
+        ],
+        1,
+    );
+    assert_eq!(patterns.len(), 1);
+    assert_eq!(patterns[0].executions.count, 4);
+    assert_eq!(patterns[0].function().unwrap(), r#"kl() { kubectl logs -f "$1" -n prod; }"#);
+}
+
+#[test]
+fn constant_arguments() {
+    let patterns = sample_patterns(
+        &["git commit -m wip", "git commit -m wip", "git commit -m fix", "git push"],
+        5,
+    );
+    assert!(patterns.is_empty());
+}
+
+#[test]
+fn several_placeholders() {
+    let patterns = sample_patterns(&["cp a x.bak", "cp b y.bak", "cp c z.bak"], 5);
+    let functions: Vec<String> = patterns.iter().filter_map(Pattern::function).collect();
+    assert!(functions.is_empty());
+
+    let patterns = sample_patterns(
+        &["scp a host:x -v", "scp b host:y -v", "scp c host:z -v"],
+        5,
+    );
+    let functions: Vec<String> = patterns.iter().filter_map(Pattern::function).collect();
+    assert_eq!(functions, vec![r#"s() { scp "$1" "$2" -v; }"#]);
+}
+
+#[test]
+fn quoted_function() {
+    let patterns = sample_patterns(
+        &[
+            "sudo git commit -m 'first try'",
+            "sudo git commit -m 'second try'",
+            "sudo git commit -m 'third try'",
+        ],
+        1,
+    );
+    assert_eq!(patterns[0].function().unwrap(), r#"gc() { sudo git commit -m "$1"; }"#);
+    assert_eq!(patterns[0].body(Shell::Bash), r#"sudo git commit -m "$1""#);
+    assert_eq!(
+        patterns[0].definition(Shell::Fish).unwrap(),
+        "function gc; sudo git commit -m $argv[1]; end"
+    );
+}
+
+#[test]
+fn order() {
+    let patterns = sample_patterns(
+        &[
+            "git checkout -b a",
+            "git checkout -b b",
+            "git checkout -b c",
+            "docker run --rm -it a bash",
+            "docker run --rm -it b bash",
+            "docker run --rm -it c bash",
+        ],
+        5,
+    );
+    let functions: Vec<String> = patterns.iter().filter_map(Pattern::function).collect();
+    assert_eq!(
+        functions,
+        vec![
+            r#"dr() { docker run --rm -it "$1" bash; }"#,
+            r#"gco() { git checkout -b "$1"; }"#,
+        ]
+    );
+}
+
+#[test]
+fn table_building() {
+    let patterns = sample_patterns(&["ssh a uptime", "ssh b uptime", "ssh c uptime"], 5);
+    let expected = table!(
+        ["Uses", "Last Used", "Function"],
+        [3, "Unknown", r#"s() { ssh "$1" uptime; }"#]
+    );
+    assert_eq!(build_table(patterns), expected);
+}
+
+#[test]
+fn rare_templates_pruned() {
+    let normalizer = Normalizer::default();
+    let lines: Vec<String> = (0..PRUNE_INTERVAL)
+        .map(|i| match i % 100 {
+            0 => format!("git push origin b{}", i),
+            _ => format!("x{} y z", i),
+        })
+        .collect();
+    let miner = mine(PatternMiner::new(&normalizer), History::from_iter(&lines));
+    // Only the templates of the frequent command are left
+    assert_eq!(miner.uses.len(), 3);
+    assert_eq!(miner.into_patterns(1).next().unwrap().executions.count, 100);
+}
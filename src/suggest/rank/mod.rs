@@ -0,0 +1,147 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use super::suggestion::Suggestion;
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Strategy for ranking suggested commands
+pub trait Ranker {
+    /// Produces the key of a suggestion, which is greater for better suggestions
+    fn key(&self, suggestion: &Suggestion) -> Vec<u64>;
+}
+
+/// Ranks short commands below those with few arguments, and those below the rest, which are
+/// ranked by their uses and then their number of arguments and length
+///
+/// This is a transitive approximation of the original pairwise ordering, which grouped two
+/// commands by the shorter of them and treated uses within 20% of each other as equal. Here each
+/// command is grouped by itself, and uses are bucketed by powers of `SIMILAR_USES`.
+pub struct Heuristic;
+
+impl Heuristic {
+    const BASELINE_LEN: usize = 10;
+    const BASELINE_ARGS: usize = 2;
+    /// Ratio of uses within which commands are ranked as if used equally often
+    const SIMILAR_USES: f64 = 1.2;
+}
+
+impl Ranker for Heuristic {
+    fn key(&self, suggestion: &Suggestion) -> Vec<u64> {
+        let length = suggestion.length as u64;
+        let args = suggestion.args.len() as u64;
+        let uses = suggestion.executions.count;
+        if suggestion.length < Self::BASELINE_LEN {
+            // Very short command--prioritize longer
+            vec![0, length, args, u64::from(uses)]
+        } else if suggestion.args.len() < Self::BASELINE_ARGS {
+            // Few arguments--prioritize more
+            vec![1, args, length, u64::from(uses)]
+        } else {
+            // Prioritize executions, treating similar counts alike
+            let similar = (f64::from(uses).ln() / Self::SIMILAR_USES.ln()).floor().max(0.0);
+            vec![2, similar as u64, args, length, u64::from(uses)]
+        }
+    }
+}
+
+/// Ranks commands by how often they were used
+pub struct Frequency;
+
+impl Ranker for Frequency {
+    fn key(&self, suggestion: &Suggestion) -> Vec<u64> {
+        vec![u64::from(suggestion.executions.count), suggestion.length as u64]
+    }
+}
+
+/// Ranks commands by when they were last used, ranking those with unknown times last
+pub struct Recency;
+
+impl Ranker for Recency {
+    fn key(&self, suggestion: &Suggestion) -> Vec<u64> {
+        let executions = &suggestion.executions;
+        let last = executions.last_executed.map_or(0, |time| time.timestamp().max(0) as u64 + 1);
+        vec![last, u64::from(executions.count)]
+    }
+}
+
+/// Ranks commands by their scores, the characters their aliases would have saved
+pub struct Savings;
+
+impl Ranker for Savings {
+    fn key(&self, suggestion: &Suggestion) -> Vec<u64> {
+        vec![suggestion.score(), u64::from(suggestion.executions.count)]
+    }
+}
+
+/// Names of the rankers that can be chosen from
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rank {
+    Heuristic,
+    Frequency,
+    Recency,
+    Savings,
+}
+
+impl Rank {
+    /// Names of the rankers, as accepted by `from_str`
+    pub const VARIANTS: &'static [&'static str] =
+        &["heuristic", "frequency", "recency", "savings"];
+
+    /// Produces the ranker of this name
+    pub fn ranker(self) -> Box<dyn Ranker> {
+        match self {
+            Rank::Heuristic => Box::new(Heuristic),
+            Rank::Frequency => Box::new(Frequency),
+            Rank::Recency => Box::new(Recency),
+            Rank::Savings => Box::new(Savings),
+        }
+    }
+}
+
+impl FromStr for Rank {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "heuristic" => Ok(Rank::Heuristic),
+            "frequency" => Ok(Rank::Frequency),
+            "recency" => Ok(Rank::Recency),
+            "savings" => Ok(Rank::Savings),
+            _ => Err(format!("Unknown ranking: {}", s)),
+        }
+    }
+}
+
+/// Represents a suggestion along with its key from a `Ranker`
+#[derive(Debug, Eq, PartialEq)]
+pub struct Ranked {
+    pub key: Vec<u64>,
+    pub suggestion: Suggestion,
+}
+
+impl Ranked {
+    /// Ranks a suggestion with `ranker`
+    pub fn new(suggestion: Suggestion, ranker: &dyn Ranker) -> Self {
+        Ranked { key: ranker.key(&suggestion), suggestion }
+    }
+}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (this, that) = (&self.suggestion, &other.suggestion);
+        self.key
+            .cmp(&other.key)
+            // Prefer the more specific of two suggestions that rank equally
+            .then(this.args.len().cmp(&that.args.len()))
+            .then(that.command.cmp(&this.command))
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -0,0 +1,137 @@
+use std::cmp::Ordering;
+
+use crate::suggest::executions::Executions;
+use crate::testing::at;
+
+use super::{Frequency, Heuristic, Rank, Ranked, Ranker, Recency, Savings, Suggestion};
+
+fn uses(count: u32) -> Executions {
+    Executions { count, ..Executions::default() }
+}
+
+fn ranked(args: &[&str], executions: Executions, ranker: &dyn Ranker) -> Ranked {
+    let mut suggestion = Suggestion::new(args.to_vec(), executions);
+    suggestion.name = suggestion.candidates().into_iter().next();
+    Ranked::new(suggestion, ranker)
+}
+
+#[test]
+fn heuristic() {
+    let a1 = &["cargo"][..];
+    let a2 = &["cargo", "run"][..];
+    let a3 = &["abc"][..];
+    let a4 = &["abcdefghijklmnop"][..];
+    let a5 = &["cargo", "run", "--release"][..];
+    let a6 = &["cargo", "run", "--debug"][..];
+    let s = |args, count| ranked(args, uses(count), &Heuristic);
+
+    // Sorting based on length and number of args
+    assert!(s(a3, 100) < s(a2, 100));
+    assert!(s(a3, 100) < s(a4, 100));
+    assert!(s(a3, 100) < s(a1, 100));
+    assert!(s(a2, 100) < s(a4, 100));
+    assert!(s(a2, 100) < s(a5, 100));
+    assert!(s(a4, 100) < s(a5, 100));
+    assert!(s(a5, 100) > s(a6, 100));
+
+    // Sorting based on executions
+    assert!(s(a5, 100) > s(a5, 50));
+    assert!(s(a5, 100) > s(a5, 10));
+    assert!(s(a5, 50) > s(a5, 10));
+
+    // Similar executions are sorted based on length
+    assert!(s(a5, 100) > s(a6, 110));
+    assert!(s(a5, 100) < s(a6, 150));
+}
+
+/// The ordering of suggestions before rankers could be chosen, which isn't transitive
+fn original_cmp(this: &Suggestion, other: &Suggestion) -> Ordering {
+    let length_ordering = this.length.cmp(&other.length);
+    let argc_ordering = this.args.len().cmp(&other.args.len());
+    let exec_ordering = this.executions.cmp(&other.executions);
+    let (this_count, other_count) =
+        (f64::from(this.executions.count), f64::from(other.executions.count));
+    let exec_count_diff = (other_count - this_count).abs() / ((other_count + this_count) / 2.0);
+    if this.length < 10 || other.length < 10 {
+        length_ordering.then(argc_ordering).then(exec_ordering)
+    } else if this.args.len() < 2 || other.args.len() < 2 || exec_count_diff < 0.2 {
+        argc_ordering.then(length_ordering).then(exec_ordering)
+    } else {
+        exec_ordering.then(argc_ordering).then(length_ordering)
+    }
+}
+
+#[test]
+fn heuristic_matches_original_order() {
+    // Every prefix of the original sample history, `abc 123`, `cargo`, `cargo run` and
+    // `cargo run --release`
+    let prefixes: &[(&[&str], u32)] = &[
+        (&["abc"], 1),
+        (&["abc", "123"], 1),
+        (&["cargo"], 3),
+        (&["cargo", "run"], 2),
+        (&["cargo", "run", "--release"], 1),
+    ];
+    let mut original: Vec<Suggestion> = prefixes
+        .iter()
+        .map(|(args, count)| Suggestion::new(args.to_vec(), uses(*count)))
+        .collect();
+    original.sort_by(|a, b| original_cmp(b, a));
+    let mut heuristic: Vec<Ranked> =
+        prefixes.iter().map(|(args, count)| ranked(args, uses(*count), &Heuristic)).collect();
+    heuristic.sort_by(|a, b| b.cmp(a));
+    let heuristic: Vec<&str> =
+        heuristic.iter().map(|ranked| ranked.suggestion.command.as_str()).collect();
+    let original: Vec<&str> =
+        original.iter().map(|suggestion| suggestion.command.as_str()).collect();
+    assert_eq!(heuristic, original);
+    assert_eq!(original[0], "cargo run --release");
+}
+
+#[test]
+fn frequency() {
+    let s = |args, count| ranked(args, uses(count), &Frequency);
+    assert!(s(&["ls"], 100) > s(&["cargo", "run", "--release"], 99));
+    assert!(s(&["cargo", "run"], 5) > s(&["ls"], 5));
+}
+
+#[test]
+fn recency() {
+    let at = |secs| {
+        let mut executions = Executions::default();
+        executions.update(at(secs), None, &[]);
+        executions
+    };
+    let s = |args, executions| ranked(args, executions, &Recency);
+    assert!(s(&["ls"], at(1556993411)) > s(&["cargo", "run"], at(1556993410)));
+    assert!(s(&["ls"], at(0)) > s(&["cargo", "run"], uses(100)));
+    let mut twice = at(5);
+    twice.update(None, None, &[]);
+    assert!(s(&["ls"], twice) > s(&["cargo"], at(5)));
+}
+
+#[test]
+fn savings() {
+    let s = |args, count| ranked(args, uses(count), &Savings);
+    assert!(s(&["cargo", "run", "--release"], 10) > s(&["cargo", "run"], 20));
+    assert!(s(&["cargo", "run", "--release"], 10) < s(&["cargo", "run"], 30));
+    assert_eq!(s(&["cargo", "run"], 2).key, vec![14, 2]);
+}
+
+#[test]
+fn ties() {
+    // Preferring more specific commands, then alphabetical ones, when keys are equal
+    let short = ranked(&["abcdef"], uses(10), &Savings);
+    let long = ranked(&["ab", "cdef"], uses(10), &Savings);
+    assert_eq!(short.key, long.key);
+    assert!(short < long);
+    assert!(ranked(&["b", "xy"], uses(10), &Savings) < ranked(&["a", "xy"], uses(10), &Savings));
+}
+
+#[test]
+fn rank_names() {
+    let ranks: Vec<Rank> = Rank::VARIANTS.iter().map(|name| name.parse().unwrap()).collect();
+    assert_eq!(ranks, vec![Rank::Heuristic, Rank::Frequency, Rank::Recency, Rank::Savings]);
+    assert_eq!("Savings".parse(), Ok(Rank::Savings));
+    assert!("random".parse::<Rank>().is_err());
+}
@@ -0,0 +1,178 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Duration, Utc};
+use prettytable::Table;
+
+use crate::capped_heap::CappedHeap;
+use crate::history::{ExecutedCommand, quote};
+use crate::normalize::{NormalizedCommand, Normalizer};
+use crate::shell::{names, Shell};
+
+use super::executions::Executions;
+use super::{Miner, subsumed};
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Most commands counted in a single sequence
+const MAX_COMMANDS: usize = 4;
+/// Fewest times a sequence must recur to be suggested
+const MIN_USES: u32 = 2;
+
+/// Represents a suggested shell function for commands that are repeatedly run one after another
+///
+/// e.g. `git fetch`, `git rebase origin/main` and `cargo test`
+#[derive(Debug, Eq, PartialEq)]
+pub struct Sequence {
+    /// Name of the function, if one is free
+    pub name: Option<String>,
+    pub commands: Vec<Vec<String>>,
+    pub executions: Executions,
+}
+
+impl Sequence {
+    /// Produces candidate names for the function from its commands, e.g. `ggc`
+    pub fn candidates(&self) -> Vec<String> {
+        let commands: Vec<&String> =
+            self.commands.iter().filter_map(|command| command.first()).collect();
+        names::candidates(&commands)
+    }
+
+    /// Produces the body of the function, running each command if the previous one succeeded
+    pub fn body(&self) -> String {
+        let commands = self.commands.iter().map(|command| {
+            command.iter().map(|arg| quote(arg)).collect::<Vec<_>>().join(" ")
+        });
+        commands.collect::<Vec<_>>().join(" && ")
+    }
+
+    /// Produces the definition of the function in bash or zsh, if it has a name, e.g.
+    /// `ggc() { git fetch && git rebase origin/main && cargo test; }`
+    pub fn function(&self) -> Option<String> {
+        self.definition(Shell::Bash)
+    }
+
+    /// Produces the definition of the function in `shell`, if it has a name
+    pub fn definition(&self, shell: Shell) -> Option<String> {
+        Some(shell.function(self.name.as_ref()?, &self.body()))
+    }
+}
+
+impl Ord for Sequence {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.executions
+            .cmp(&other.executions)
+            // Prefer the longer of two sequences that recur as often
+            .then(self.commands.len().cmp(&other.commands.len()))
+            .then(other.commands.cmp(&self.commands))
+    }
+}
+
+impl PartialOrd for Sequence {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Mines commands for sequences that are repeatedly run one after another
+///
+/// Commands are only part of a sequence if all of them were run within `window` of the first,
+/// as far as their times are known. Sequences never span a command run before the previous one,
+/// nor the end of a history file, see `reset`.
+pub struct SequenceMiner<'a> {
+    normalizer: &'a Normalizer,
+    window: Duration,
+    /// The latest commands, including their wrappers, and when they were run
+    recent: VecDeque<(Vec<String>, Option<DateTime<Utc>>)>,
+    uses: HashMap<Vec<Vec<String>>, Executions>,
+}
+
+impl<'a> SequenceMiner<'a> {
+    /// Initializes a `SequenceMiner` normalizing commands with `normalizer`
+    pub fn new(normalizer: &'a Normalizer, window: Duration) -> Self {
+        SequenceMiner {
+            normalizer,
+            window,
+            recent: VecDeque::with_capacity(MAX_COMMANDS),
+            uses: HashMap::new(),
+        }
+    }
+
+    /// Forgets the latest commands, so that the next command doesn't continue their sequences
+    ///
+    /// This is called between history files, whose commands weren't run one after another.
+    pub fn reset(&mut self) {
+        self.recent.clear();
+    }
+
+    /// Produces an iterator of the `n` sequences that recurred most often
+    ///
+    /// Sequences that recurred at least `MIN_USES` times are included, unless a longer sequence
+    /// containing them recurred just as often.
+    pub fn into_sequences(self, n: usize) -> impl Iterator<Item=Sequence> {
+        let subsumed = subsumed(&self.uses);
+        let mut heap = CappedHeap::new(n);
+        for (commands, executions) in &self.uses {
+            if executions.count >= MIN_USES && !subsumed.contains(&commands[..]) {
+                heap.insert(Sequence {
+                    name: None,
+                    commands: commands.to_owned(),
+                    executions: executions.clone(),
+                });
+            }
+        }
+        heap.heap.into_vec_desc().into_iter()
+    }
+}
+
+impl<'a> Miner for SequenceMiner<'a> {
+    /// Counts an execution of a command, and of each sequence it ends
+    fn add(&mut self, command: ExecutedCommand) {
+        let NormalizedCommand { args, wrapper, time, .. } = self.normalizer.normalize(command);
+        let previous = self.recent.back().and_then(|(_, time)| *time);
+        if let (Some(previous), Some(time)) = (previous, time) {
+            if time < previous {
+                self.reset();
+            }
+        }
+        if self.recent.len() == MAX_COMMANDS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back((wrapper.into_iter().chain(args).collect(), time));
+
+        let last = self.recent.len() - 1;
+        for start in 0..last {
+            let within_window = match (self.recent[start].1, time) {
+                (Some(first), Some(time)) => time.signed_duration_since(first) <= self.window,
+                _ => true,
+            };
+            let commands: Vec<Vec<String>> =
+                self.recent.range(start..).map(|(command, _)| command.to_owned()).collect();
+            let repeated = commands.iter().all(|command| *command == commands[0]);
+            if within_window && !repeated {
+                self.uses.entry(commands).or_default().update(time, None, &[]);
+            }
+        }
+    }
+}
+
+/// Converts an iterator of sequences into a table
+pub fn build_table<I>(sequences: I) -> Table
+    where
+        I: IntoIterator<Item=Sequence>,
+{
+    let mut table = table!(["Uses", "Last Used", "Function"]);
+    for sequence in sequences {
+        table.add_row(row![
+            sequence.executions.count,
+            sequence
+                .executions
+                .last_executed_str()
+                .unwrap_or("Unknown".to_string()),
+            sequence.function().unwrap_or_default(),
+        ]);
+    }
+    table
+}
@@ -0,0 +1,126 @@
+use std::iter::FromIterator;
+
+use chrono::Duration;
+
+use crate::history::History;
+use crate::normalize::Normalizer;
+use crate::shell::names::Namer;
+use crate::shell::Shell;
+use crate::testing;
+
+use super::{build_table, Sequence, SequenceMiner};
+
+fn mine(lines: &[&str], window: i64, n: usize) -> Vec<Sequence> {
+    mine_files(&[lines], window, n)
+}
+
+fn mine_files(files: &[&[&str]], window: i64, n: usize) -> Vec<Sequence> {
+    let normalizer = Normalizer::default();
+    let mut miner = SequenceMiner::new(&normalizer, Duration::seconds(window));
+    for lines in files {
+        miner.reset();
+        miner = testing::mine(miner, History::from_iter(lines.iter()));
+    }
+    let mut namer = Namer::new();
+    let mut sequences: Vec<Sequence> = miner.into_sequences(n).collect();
+    for sequence in &mut sequences {
+        sequence.name = namer.choose(sequence.candidates());
+    }
+    sequences
+}
+
+fn functions(sequences: &[Sequence]) -> Vec<String> {
+    sequences.iter().filter_map(Sequence::function).collect()
+}
+
+#[test]
+fn repeated_workflow() {
+    let sequences = mine(
+        &[
+            "git fetch",
+            "git rebase origin/main",
+            "cargo test",
+            "vim src/main.rs",
+            "git fetch",
+            "git rebase origin/main",
+            "cargo test",
+        ],
+        300,
+        5,
+    );
+    assert_eq!(
+        functions(&sequences),
+        vec!["ggc() { git fetch && git rebase origin/main && cargo test; }"]
+    );
+    assert_eq!(sequences[0].executions.count, 2);
+    assert_eq!(
+        sequences[0].definition(Shell::Fish).unwrap(),
+        "function ggc; git fetch && git rebase origin/main && cargo test; end"
+    );
+}
+
+#[test]
+fn compound_lines() {
+    let sequences = mine(&["make && ./run 'a b'", "make && ./run 'a b'", "ls"], 300, 5);
+    assert_eq!(functions(&sequences), vec!["mr() { make && ./run 'a b'; }"]);
+}
+
+#[test]
+fn repeated_command() {
+    let sequences = mine(&["ls", "ls", "ls", "ls"], 300, 5);
+    assert!(sequences.is_empty());
+}
+
+#[test]
+fn time_window() {
+    let lines = [
+        ": 1556990000:0;git pull",
+        ": 1556990010:0;make",
+        ": 1556991000:0;git pull",
+        ": 1556994000:0;make",
+    ];
+    assert!(mine(&lines, 300, 5).is_empty());
+    assert_eq!(functions(&mine(&lines, 3600, 5)), vec!["gm() { git pull && make; }"]);
+}
+
+#[test]
+fn backwards_times() {
+    let lines = [
+        ": 1556990000:0;git pull",
+        ": 1556990010:0;make",
+        ": 1556990020:0;git pull",
+        ": 1556980000:0;make",
+    ];
+    assert!(mine(&lines, 300, 5).is_empty());
+}
+
+#[test]
+fn file_boundaries() {
+    let files: &[&[&str]] = &[&["git pull"], &["make", "git pull"], &["make"]];
+    assert!(mine_files(files, 300, 5).is_empty());
+    let files: &[&[&str]] = &[&["git pull", "make"], &["git pull", "make"]];
+    assert_eq!(functions(&mine_files(files, 300, 5)), vec!["gm() { git pull && make; }"]);
+}
+
+#[test]
+fn order() {
+    let sequences = mine(
+        &["a", "b", "c", "a", "b", "x", "a", "b", "c", "y", "a", "b"],
+        300,
+        5,
+    );
+    assert_eq!(
+        functions(&sequences),
+        vec!["ab() { a && b; }", "abc() { a && b && c; }"]
+    );
+}
+
+#[test]
+fn table_building() {
+    let sequences = mine(&["make", "make test", "make", "make test"], 300, 1);
+    let expected = table!(
+        ["Uses", "Last Used", "Function"],
+        [2, "Unknown", "mm() { make && make test; }"]
+    );
+    assert_eq!(build_table(sequences), expected);
+}
@@ -0,0 +1,83 @@
+use std::cmp::Ordering;
+
+use prettytable::Table;
+
+use crate::history::ExecutedCommand;
+use crate::normalize::Normalizer;
+use crate::trie::Trie;
+
+use super::executions::format_duration;
+use super::suggestion::Suggestion;
+use super::was_typed;
+
+#[cfg(test)]
+#[cfg_attr(tarpaulin, skip)]
+mod tests;
+
+/// Represents a command ranked by the total time spent running it
+#[derive(Debug, Eq, PartialEq)]
+pub struct SlowCommand {
+    pub suggestion: Suggestion,
+}
+
+impl Ord for SlowCommand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (this, other) = (&self.suggestion, &other.suggestion);
+        this.executions
+            .total_duration
+            .cmp(&other.executions.total_duration)
+            .then(this.executions.count.cmp(&other.executions.count))
+            // Prefer the more specific of two equally slow commands
+            .then(this.args.len().cmp(&other.args.len()))
+            .then(other.command.cmp(&this.command))
+    }
+}
+
+impl PartialOrd for SlowCommand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Produces an iterator of the `n` commands that took the most time to run in total
+///
+/// Only commands with a recorded duration, such as those in zsh's extended history, are included.
+pub fn slowest<I>(
+    commands: I,
+    n: usize,
+    normalizer: &Normalizer,
+) -> impl Iterator<Item=SlowCommand>
+    where
+        I: IntoIterator<Item=ExecutedCommand>,
+{
+    let trie = Trie::from_commands(commands, normalizer);
+    let slowest = trie.drain_top_items(n, |args, executions| {
+        if executions.total_duration == 0 || !was_typed(&executions, normalizer) {
+            None
+        } else {
+            let suggestion = Suggestion::new(args, executions);
+            Some(SlowCommand { suggestion })
+        }
+    });
+    slowest.into_iter()
+}
+
+/// Converts an iterator of slow commands into a table
+pub fn build_table<I>(commands: I) -> Table
+    where
+        I: IntoIterator<Item=SlowCommand>,
+{
+    let mut table = table!(["Total Time", "Mean Time", "Uses", "Command"]);
+    for SlowCommand { suggestion } in commands {
+        let executions = suggestion.executions;
+        table.add_row(row![
+            format_duration(executions.total_duration),
+            executions
+                .mean_duration()
+                .map_or("Unknown".to_string(), |mean| format_duration(mean.round() as u64)),
+            executions.count,
+            suggestion.command,
+        ]);
+    }
+    table
+}
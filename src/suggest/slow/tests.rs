@@ -0,0 +1,63 @@
+use std::iter::FromIterator;
+
+use crate::history::{History, ParseOptions};
+use crate::normalize::Normalizer;
+
+use super::{build_table, slowest, SlowCommand};
+
+fn sample_hist() -> History {
+    History::from_iter(&[
+        ": 1556993411:0;cargo fmt",
+        ": 1556993412:120;cargo build --release",
+        ": 1556993532:30;cargo test",
+        ": 1556993562:90;cargo build --release",
+        ": 1556993652:5;git status && git diff",
+    ])
+}
+
+fn sample_slowest(n: usize) -> impl Iterator<Item=SlowCommand> {
+    slowest(sample_hist().commands, n, &Normalizer::default())
+}
+
+fn commands(slow: Vec<SlowCommand>) -> Vec<String> {
+    slow.into_iter().map(|slow| slow.suggestion.command).collect()
+}
+
+#[test]
+fn slowest_commands() {
+    let slow: Vec<SlowCommand> = sample_slowest(3).collect();
+    assert_eq!(
+        commands(slow),
+        vec!["cargo", "cargo build --release", "cargo build"]
+    );
+}
+
+#[test]
+fn untimed_commands_excluded() {
+    let slow: Vec<SlowCommand> = sample_slowest(10).collect();
+    let slow = commands(slow);
+    assert_eq!(slow.len(), 4);
+    assert!(!slow.contains(&"cargo fmt".to_string()));
+    // The duration of a compound line can't be attributed to any of its commands
+    assert!(!slow.contains(&"git".to_string()));
+}
+
+#[test]
+fn whole_lines_timed() {
+    let options = ParseOptions { whole_lines: true, ..ParseOptions::default() };
+    let hist = History::parse([": 1556993652:5;git status && git diff"], options);
+    let slow: Vec<SlowCommand> = slowest(hist.commands, 10, &Normalizer::default()).collect();
+    let line = ["git", "status", "&&", "git", "diff"];
+    assert!(slow.iter().any(|slow| slow.suggestion.args == line));
+}
+
+#[test]
+fn table_building() {
+    let slow: Vec<SlowCommand> = sample_slowest(2).collect();
+    let expected = table!(
+        ["Total Time", "Mean Time", "Uses", "Command"],
+        ["4m 00s", "1m 00s", 4, "cargo"],
+        ["3m 30s", "1m 45s", 2, "cargo build --release"]
+    );
+    assert_eq!(build_table(slow), expected);
+}
@@ -1,4 +1,5 @@
-use std::cmp::Ordering;
+use crate::history::quote;
+use crate::shell::{names, Shell};
 
 use super::executions::Executions;
 
@@ -7,61 +8,65 @@ use super::executions::Executions;
 mod tests;
 
 /// Represents a suggested command to alias
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, PartialEq)]
 pub struct Suggestion {
+    /// Name of an alias for the command, if one is free
+    pub name: Option<String>,
     pub command: String,
     pub length: usize,
     pub args: Vec<String>,
     pub executions: Executions,
+    /// How much each use counts towards the score, between 0 and 1, e.g. less for older commands
+    pub weight: f64,
 }
 
 impl Suggestion {
-    const BASELINE_LEN: usize = 10;
-    const BASELINE_ARGS: usize = 2;
-
     /// Initializes a `Suggestion`
+    ///
+    /// The command includes the wrapper, like `sudo`, that all of its executions were run under,
+    /// and is spelled the way it was most often entered if its flags were canonicalized. It has
+    /// no name until one is chosen from its candidates.
     pub fn new<T>(args: Vec<T>, executions: Executions) -> Self
         where T: Into<String> {
         let args: Vec<String> = args.into_iter().map(|arg| arg.into()).collect();
-        let command = args.join(" ");
+        let wrapper = executions.wrapper.as_deref().unwrap_or_default();
+        let spelling = executions.spelling().unwrap_or(&args);
+        let words: Vec<_> = wrapper.iter().chain(spelling).map(|arg| quote(arg)).collect();
+        let command = words.join(" ");
         let length = command.len();
         Suggestion {
+            name: None,
             command,
             length,
             args,
             executions,
+            weight: 1.0,
         }
     }
-}
 
-impl Ord for Suggestion {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let length_ordering = self.length.cmp(&other.length);
-        let argc_ordering = self.args.len().cmp(&other.args.len());
-        let exec_ordering = self.executions.cmp(&other.executions);
+    /// Produces the number of characters the alias saves each time it's used instead of the
+    /// command, which is none if it has no name
+    pub fn savings(&self) -> usize {
+        self.name.as_ref().map_or(0, |name| self.length.saturating_sub(name.len()))
+    }
 
-        let exec_count_avg = (other.executions.count as f64 + self.executions.count as f64) / 2.0;
-        let exec_count_diff =
-            (other.executions.count as f64 - self.executions.count as f64).abs() / exec_count_avg;
+    /// Produces the number of characters the alias would have saved across the command's uses,
+    /// weighted by `weight`
+    pub fn score(&self) -> u64 {
+        let saved = self.savings() as f64 * f64::from(self.executions.count);
+        (saved * self.weight).round() as u64
+    }
 
-        if self.length < Self::BASELINE_LEN || other.length < Self::BASELINE_LEN {
-            // Very short command--prioritize longer
-            length_ordering.then(argc_ordering).then(exec_ordering)
-        } else if self.args.len() < Self::BASELINE_ARGS || other.args.len() < Self::BASELINE_ARGS {
-            // Few arguments--prioritize more
-            argc_ordering.then(length_ordering).then(exec_ordering)
-        } else if exec_count_diff < 0.2 {
-            // Similar executions--prioritize length and argc
-            argc_ordering.then(length_ordering).then(exec_ordering)
-        } else {
-            // Prioritize executions
-            exec_ordering.then(argc_ordering).then(length_ordering)
-        }
+    /// Produces candidate names for an alias of the command, e.g. `crr` for `cargo run --release`
+    pub fn candidates(&self) -> Vec<String> {
+        names::candidates(&self.args)
     }
-}
 
-impl PartialOrd for Suggestion {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(&other))
+    /// Produces the definition of an alias of the command in `shell`, if it has a name
+    pub fn definition(&self, shell: Shell) -> Option<String> {
+        Some(shell.alias(self.name.as_ref()?, &self.command))
     }
 }
+
+// Weights are never NaN
+impl Eq for Suggestion {}
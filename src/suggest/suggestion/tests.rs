@@ -1,3 +1,6 @@
+use crate::history::split_commands;
+use crate::shell::Shell;
+
 use super::{Executions, Suggestion};
 
 #[test]
@@ -8,49 +11,83 @@ fn test_new() {
             Executions::default(),
         ),
         Suggestion {
+            name: None,
             command: "cargo run".to_string(),
             length: 9,
             args: vec!["cargo".to_string(), "run".to_string()],
             executions: Executions::default(),
+            weight: 1.0,
         }
     );
 }
 
 #[test]
-fn order() {
-    let a1 = vec!["cargo".to_string()];
-    let a2 = vec!["cargo".to_string(), "run".to_string()];
-    let a3 = vec!["abc".to_string()];
-    let a4 = vec!["abcdefghijklmnop".to_string()];
-    let a5 = vec!["cargo".to_string(), "run".to_string(), "--release".to_string()];
-    let a6 = vec!["cargo".to_string(), "run".to_string(), "--debug".to_string()];
-
-    let exec_high = Executions {
-        count: 100,
-        last_executed: None,
-    };
-    let exec_med = Executions {
-        count: 50,
-        last_executed: None,
-    };
-    let exec_low = Executions {
-        count: 10,
-        last_executed: None,
-    };
-
-    let s = Suggestion::new;
-
-    // Sorting based on length and number of args
-    assert!(s(a3.to_owned(), exec_high) < s(a2.to_owned(), exec_high));
-    assert!(s(a3.to_owned(), exec_high) < s(a4.to_owned(), exec_high));
-    assert!(s(a3.to_owned(), exec_high) < s(a1.to_owned(), exec_high));
-    assert!(s(a2.to_owned(), exec_high) < s(a4.to_owned(), exec_high));
-    assert!(s(a2.to_owned(), exec_high) < s(a5.to_owned(), exec_high));
-    assert!(s(a4.to_owned(), exec_high) < s(a5.to_owned(), exec_high));
-    assert!(s(a5.to_owned(), exec_high) > s(a6.to_owned(), exec_high));
-
-    // Sorting based on executions
-    assert!(s(a5.to_owned(), exec_high) > s(a5.to_owned(), exec_med));
-    assert!(s(a5.to_owned(), exec_high) > s(a5.to_owned(), exec_low));
-    assert!(s(a5.to_owned(), exec_med) > s(a5.to_owned(), exec_low));
+fn quoted_command() {
+    let suggestion = Suggestion::new(vec!["grep", "foo bar"], Executions::default());
+    assert_eq!(suggestion.command, "grep 'foo bar'");
+    assert_eq!(suggestion.args, vec!["grep".to_string(), "foo bar".to_string()]);
+}
+
+#[test]
+fn wrapped_command() {
+    let sudo = || Some(vec!["sudo".to_string()]);
+    let mut always = Executions::default();
+    always.update(None, None, &sudo().unwrap());
+    let suggestion = Suggestion::new(vec!["systemctl", "restart"], always.clone());
+    assert_eq!(suggestion.command, "sudo systemctl restart");
+    assert_eq!(suggestion.args, vec!["systemctl", "restart"]);
+    assert_eq!(always.wrapper, sudo());
+
+    let mut sometimes = always;
+    sometimes.update(None, None, &[]);
+    assert_eq!(sometimes.wrapper, None);
+    let suggestion = Suggestion::new(vec!["systemctl", "restart"], sometimes);
+    assert_eq!(suggestion.command, "systemctl restart");
+}
+
+#[test]
+fn definitions() {
+    let mut suggestion = Suggestion::new(vec!["cargo", "run", "--release"], Executions::default());
+    assert_eq!(suggestion.definition(Shell::Bash), None);
+    assert_eq!(suggestion.candidates(), vec!["crr", "crre", "crrel"]);
+    suggestion.name = Some("crr".to_string());
+    assert_eq!(
+        suggestion.definition(Shell::Bash),
+        Some("alias crr='cargo run --release'".to_string())
+    );
+    assert_eq!(
+        suggestion.definition(Shell::Fish),
+        Some("abbr -a crr 'cargo run --release'".to_string())
+    );
+
+    // Expansions are left for the shell to perform whenever the alias is used
+    let mut suggestion = Suggestion::new(vec!["echo", "$HOME"], Executions::default());
+    suggestion.name = Some("eh".to_string());
+    assert_eq!(suggestion.definition(Shell::Zsh), Some("alias eh='echo $HOME'".to_string()));
+
+    // Quoted text stays quoted
+    let args = split_commands("echo '$HOME'").remove(0);
+    let mut suggestion = Suggestion::new(args, Executions::default());
+    suggestion.name = Some("eh".to_string());
+    assert_eq!(suggestion.command, "echo '$HOME'");
+    assert_eq!(
+        suggestion.definition(Shell::Bash),
+        Some(r"alias eh='echo '\''$HOME'\'''".to_string())
+    );
+}
+
+#[test]
+fn scores() {
+    let uses = |count| Executions { count, ..Executions::default() };
+    let mut suggestion = Suggestion::new(vec!["cargo", "run", "--release"], uses(10));
+    assert_eq!(suggestion.score(), 0);
+    suggestion.name = Some("crr".to_string());
+    assert_eq!(suggestion.savings(), 16);
+    assert_eq!(suggestion.score(), 160);
+    suggestion.weight = 0.25;
+    assert_eq!(suggestion.score(), 40);
+    suggestion.name = Some("release".to_string());
+    assert_eq!(suggestion.savings(), 12);
+    suggestion.name = None;
+    assert_eq!(suggestion.score(), 0);
 }
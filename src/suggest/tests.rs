@@ -1,65 +1,198 @@
+use std::collections::HashSet;
 use std::iter::FromIterator;
 
+use chrono::Duration;
+
 use crate::history::History;
+use crate::normalize::Normalizer;
+use crate::shell::names::Namer;
 
-use super::{build_table, executions::Executions, suggest, suggestion::Suggestion};
+use super::{build_table, executions::Executions, Ranking, suggest, suggestion::Suggestion};
+use super::rank::{Frequency, Ranker, Savings};
 
 fn sample_hist() -> History {
     History::from_iter(&["abc 123", "cargo", "cargo run", "cargo run --release"])
 }
 
+fn named(args: &[&str], count: u32) -> Suggestion {
+    let mut suggestion = Suggestion::new(args.to_vec(), Executions {
+        count,
+        last_executed: None,
+        ..Executions::default()
+    });
+    suggestion.name = suggestion.candidates().into_iter().next();
+    suggestion
+}
+
 fn sample_suggestions() -> Vec<Suggestion> {
-    vec![
-        Suggestion::new(
-            vec!["cargo", "run", "--release"],
-            Executions {
-                count: 1,
-                last_executed: None,
-            },
-        ),
-        Suggestion::new(
-            vec!["cargo", "run"],
-            Executions {
-                count: 2,
-                last_executed: None,
-            },
-        ),
-    ]
+    vec![named(&["cargo", "run", "--release"], 1), named(&["cargo", "run"], 2)]
+}
+
+fn suggested(
+    hist: History,
+    n: usize,
+    normalizer: &Normalizer,
+    covered: &HashSet<Vec<String>>,
+    half_life: Option<Duration>,
+    ranker: &dyn Ranker,
+) -> Vec<Suggestion> {
+    let ranking = Ranking { half_life, ranker, namer: &mut Namer::new() };
+    suggest(hist.commands, n, normalizer, covered, ranking).collect()
+}
+
+fn sample_suggest(n: usize) -> Vec<Suggestion> {
+    suggested(sample_hist(), n, &Normalizer::default(), &HashSet::new(), None, &Savings)
+}
+
+fn commands(suggestions: Vec<Suggestion>) -> Vec<String> {
+    suggestions.into_iter().map(|suggestion| suggestion.command).collect()
 }
 
 #[test]
 fn suggest_none() {
-    assert_eq!(suggest(sample_hist(), 0).next(), None);
+    assert!(sample_suggest(0).is_empty());
 }
 
 #[test]
 fn suggest_one() {
-    let suggestions: Vec<Suggestion> = suggest(sample_hist(), 1).collect();
+    let suggestions = sample_suggest(1);
     let expected = &sample_suggestions()[..1];
     assert_eq!(&suggestions[..1], expected);
 }
 
 #[test]
 fn suggest_two() {
-    let suggestions: Vec<Suggestion> = suggest(sample_hist(), 2).collect();
-    assert_eq!(suggestions, sample_suggestions());
+    assert_eq!(sample_suggest(2), sample_suggestions());
 }
 
 #[test]
 fn suggest_all() {
-    let mut suggestions: Vec<Suggestion> = suggest(sample_hist(), 5).collect();
-    assert_eq!(suggestions.len(), 5);
-    suggestions = suggest(sample_hist(), 20).collect();
-    assert_eq!(suggestions.len(), 5);
+    assert_eq!(sample_suggest(5).len(), 5);
+    assert_eq!(sample_suggest(20).len(), 5);
+}
+
+#[test]
+fn suggest_wrapped_commands() {
+    let hist = History::from_iter(&[
+        "sudo systemctl restart nginx",
+        "sudo -u root systemctl restart nginx",
+        "FOO=1 cargo test",
+        "FOO=1 cargo test",
+    ]);
+    let normalizer = Normalizer::default();
+    let commands = commands(suggested(hist, 10, &normalizer, &HashSet::new(), None, &Savings));
+    assert!(commands.contains(&"systemctl restart nginx".to_string()));
+    assert!(commands.contains(&"FOO=1 cargo test".to_string()));
+    assert!(!commands.iter().any(|command| command.starts_with("sudo")));
+}
+
+#[test]
+fn suggest_canonical_flags() {
+    let hist = History::from_iter(&[
+        "rg --files --hidden",
+        "rg --hidden --files",
+        "ls -la",
+        "ls -l -a",
+        "ls -la",
+    ]);
+    let normalizer = Normalizer {
+        canonical_flags: true,
+        ..Normalizer::default()
+    };
+    let suggestions = suggested(hist, 10, &normalizer, &HashSet::new(), None, &Savings);
+    let uses = |command: &str| {
+        let suggestion = suggestions.iter().find(|suggestion| suggestion.command == command);
+        suggestion.map(|suggestion| suggestion.executions.count)
+    };
+    assert_eq!(uses("rg --files --hidden"), Some(2));
+    assert_eq!(uses("rg --hidden --files"), None);
+    assert_eq!(uses("ls -la"), Some(3));
+    assert_eq!(uses("ls -l -a"), None);
+    // Prefixes of expanded bundles that were never typed aren't suggested
+    assert_eq!(uses("ls -a"), None);
+}
+
+#[test]
+fn suggest_uncovered_commands() {
+    let covered: HashSet<Vec<String>> =
+        vec![vec!["cargo".to_string(), "run".to_string()]].into_iter().collect();
+    let normalizer = Normalizer::default();
+    let commands = commands(suggested(sample_hist(), 10, &normalizer, &covered, None, &Savings));
+    assert!(commands.contains(&"cargo run --release".to_string()));
+    assert!(!commands.contains(&"cargo run".to_string()));
+}
+
+#[test]
+fn suggest_recent_commands() {
+    let hist = || {
+        History::from_iter(&[
+            ": 1556000000:0;docker compose up",
+            ": 1556000001:0;docker compose up",
+            ": 1556000002:0;docker compose up",
+            ": 1556990000:0;cargo test --all",
+            ": 1556990001:0;cargo test --all",
+        ])
+    };
+    let top = |half_life| {
+        let normalizer = Normalizer::default();
+        let covered = HashSet::new();
+        commands(suggested(hist(), 1, &normalizer, &covered, half_life, &Savings))
+    };
+    assert_eq!(top(None), vec!["docker compose up"]);
+    assert_eq!(top(Some(Duration::days(1))), vec!["cargo test --all"]);
+}
+
+#[test]
+fn suggest_ranked_commands() {
+    let normalizer = Normalizer::default();
+    let top = |ranker: &dyn Ranker| {
+        commands(suggested(sample_hist(), 1, &normalizer, &HashSet::new(), None, ranker))
+    };
+    assert_eq!(top(&Savings), vec!["cargo run --release"]);
+    assert_eq!(top(&Frequency), vec!["cargo"]);
+}
+
+#[test]
+fn suggest_named_commands() {
+    // Commands are ranked by the names they can actually get
+    let normalizer = Normalizer::default();
+    let mut namer = Namer::new();
+    namer.reserve(vec!["crr".to_string()]);
+    let ranking = Ranking { half_life: None, ranker: &Savings, namer: &mut namer };
+    let suggestions: Vec<Suggestion> =
+        suggest(sample_hist().commands, 2, &normalizer, &HashSet::new(), ranking).collect();
+    let names: Vec<_> = suggestions.iter().map(|suggestion| suggestion.name.as_deref()).collect();
+    assert_eq!(names, vec![Some("crre"), Some("cr")]);
+    assert_eq!(suggestions[0].score(), 15);
+
+    // Suggestions whose names were taken by better ones are ranked by the names they got
+    let hist = History::from_iter(&[
+        "foo bar", "foo bar", "foo bar", "fib baz", "fib baz", "xyzw", "xyzw", "xyzw",
+    ]);
+    let suggestions = suggested(hist, 3, &normalizer, &HashSet::new(), None, &Savings);
+    let ranked: Vec<(&str, Option<&str>, u64)> = suggestions
+        .iter()
+        .map(|suggestion| {
+            (suggestion.command.as_str(), suggestion.name.as_deref(), suggestion.score())
+        })
+        .collect();
+    assert_eq!(
+        ranked,
+        vec![
+            ("foo bar", Some("fb"), 15),
+            ("xyzw", Some("x"), 9),
+            ("fib baz", Some("fba"), 8),
+        ]
+    );
 }
 
 #[test]
 fn table_building() {
     let table = build_table(sample_suggestions());
     let expected = table!(
-        ["Uses", "Last Used", "Command"],
-        [1, "Unknown", "cargo run --release"],
-        [2, "Unknown", "cargo run"]
+        ["Score", "Uses", "Last Used", "Name", "Command"],
+        [16, 1, "Unknown", "crr", "cargo run --release"],
+        [14, 2, "Unknown", "cr", "cargo run"]
     );
     assert_eq!(table, expected);
 }
@@ -0,0 +1,22 @@
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::history::History;
+use crate::suggest::Miner;
+
+/// Converts string slices into owned strings
+pub fn strings(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+/// Produces the time `secs` seconds after the Unix epoch, as recorded in history files
+pub fn at(secs: i64) -> Option<DateTime<Utc>> {
+    Some(Utc.timestamp(secs, 0))
+}
+
+/// Counts the commands of a history with `miner`
+pub fn mine<M: Miner>(mut miner: M, history: History) -> M {
+    for command in history.commands {
+        miner.add(command);
+    }
+    miner
+}
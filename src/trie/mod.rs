@@ -1,3 +1,4 @@
+use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::hash::Hash;
 
@@ -29,24 +30,38 @@ impl<K: TrieKey, V: TrieValue> Trie<K, V> {
         }
     }
 
-    /// Updates values along the path of a key using a function
+    /// Updates values along the path of a key using a function of each node's depth and value
     ///
     /// If nodes along the path are missing, they are initialized and then updated
     pub fn update_path<I, Q, F>(&mut self, key: I, f: F)
     where
         I: IntoIterator<Item = Q>,
         K: From<Q>,
-        F: Fn(&V) -> V,
+        F: Fn(usize, &mut V),
     {
-        let mut target = key.into_iter().fold(self, |node, fragment| {
+        let mut depth = 0;
+        let target = key.into_iter().fold(self, |node, fragment| {
             // Update current node's value
-            node.value = f(&node.value);
+            f(depth, &mut node.value);
+            depth += 1;
             // Find or create next node in path
             node.children
                 .entry(K::from(fragment))
                 .or_insert_with(Trie::new)
         });
         // Update final node's value
-        target.value = f(&target.value);
+        f(depth, &mut target.value);
+    }
+
+    /// Attempts to produce a reference to a key's value
+    pub fn get<'a, I, Q>(&self, key: I) -> Option<&V>
+        where
+            I: IntoIterator<Item=&'a Q>,
+            K: Borrow<Q>,
+            Q: Hash + Eq + ?Sized + 'a,
+    {
+        key.into_iter()
+            .try_fold(self, |trie, fragment| trie.children.get(fragment))
+            .map(|node| &node.value)
     }
 }
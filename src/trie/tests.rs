@@ -1,14 +1,5 @@
 use crate::trie::Trie;
 
-impl Trie<&'static str, u32> {
-    /// Attempts to produce a reference to a key's value
-    pub fn get(&self, key: Vec<&'static str>) -> Option<&u32> {
-        key.into_iter()
-            .try_fold(self, |trie, fragment| trie.children.get(&fragment))
-            .map(|node| &node.value)
-    }
-}
-
 fn init_trie() -> Trie<&'static str, u32> {
     let mut trie = Trie::new();
     let keys = vec![
@@ -19,7 +10,7 @@ fn init_trie() -> Trie<&'static str, u32> {
         vec!["ls"],
     ];
     for key in keys {
-        trie.update_path(key, |v| v + 1)
+        trie.update_path(key, |_, v| *v += 1)
     }
     trie
 }
@@ -36,4 +27,8 @@ fn trie_construction() {
     // Intermediate values
     assert_eq!(trie.get(vec!["ls", "-l"]), Some(&3));
     assert_eq!(trie.get(vec!["ls"]), Some(&4));
+
+    // Missing keys
+    assert_eq!(trie.get(vec!["ls", "-a"]), None);
+    assert_eq!(trie.get(vec!["rm"]), None);
 }